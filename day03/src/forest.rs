@@ -0,0 +1,355 @@
+use std::fmt;
+
+// A kind of terrain and what it costs to pass through it
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Terrain {
+    pub name: String,
+    pub cost: u32,
+}
+
+// Which input character means which `Terrain`, several characters can share one terrain
+#[derive(Debug, Clone)]
+pub struct Legend {
+    terrains: Vec<Terrain>,
+    // symbol and the index of its terrain
+    symbols: Vec<(u8, usize)>,
+}
+
+impl Legend {
+    pub fn new() -> Legend {
+        Legend {
+            terrains: Vec::new(),
+            symbols: Vec::new(),
+        }
+    }
+
+    // A later entry for the same symbol replaces the earlier one,
+    // a later entry for the same terrain name updates its cost
+    pub fn with(mut self, symbol: char, name: &str, cost: u32) -> Legend {
+        assert!(symbol.is_ascii(), "legend symbols must be ASCII");
+        let index = match self.terrains.iter().position(|t| t.name == name) {
+            Some(index) => {
+                self.terrains[index].cost = cost;
+                index
+            }
+            None => {
+                self.terrains.push(Terrain {
+                    name: name.to_string(),
+                    cost,
+                });
+                self.terrains.len() - 1
+            }
+        };
+        self.symbols.retain(|(s, _)| *s != symbol as u8);
+        self.symbols.push((symbol as u8, index));
+        self
+    }
+
+    fn index(&self, symbol: u8) -> Option<usize> {
+        self.symbols
+            .iter()
+            .find(|(s, _)| *s == symbol)
+            .map(|(_, index)| *index)
+    }
+
+    pub fn terrain(&self, symbol: char) -> Option<&Terrain> {
+        if !symbol.is_ascii() {
+            return None;
+        }
+        self.index(symbol as u8).map(|index| &self.terrains[index])
+    }
+
+    pub fn terrains(&self) -> &[Terrain] {
+        &self.terrains
+    }
+}
+
+impl Default for Legend {
+    // The puzzle legend, every tree costs 1 so a path's cost is its tree count
+    fn default() -> Legend {
+        Legend::new().with('.', "open", 0).with('#', "tree", 1)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum ForestError {
+    UnknownTerrain {
+        line: usize,
+        column: usize,
+        symbol: char,
+    },
+    // No rows at all
+    Empty,
+    // A row that isn't as wide as the first one
+    Ragged {
+        line: usize,
+        width: usize,
+        expected: usize,
+    },
+}
+
+impl fmt::Display for ForestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ForestError::UnknownTerrain {
+                line,
+                column,
+                symbol,
+            } => write!(
+                f,
+                "unknown terrain {:?} at line {}, column {}",
+                symbol, line, column
+            ),
+            ForestError::Empty => f.write_str("forest has no rows"),
+            ForestError::Ragged {
+                line,
+                width,
+                expected,
+            } => write!(
+                f,
+                "line {} is {} lots wide, expected {}",
+                line, width, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ForestError {}
+
+#[derive(Debug)]
+pub struct Forest {
+    // terrain index of every lot
+    matrix: Vec<Vec<usize>>,
+    symbols: Vec<Vec<u8>>,
+    legend: Legend,
+}
+
+impl Forest {
+    pub fn new(s: &str) -> Forest {
+        match Forest::with_legend(s, Legend::default()) {
+            Ok(forest) => forest,
+            Err(e) => panic!("Bad Forest input! {}", e),
+        }
+    }
+
+    pub fn with_legend(s: &str, legend: Legend) -> Result<Forest, ForestError> {
+        let symbols: Vec<Vec<u8>> = s.lines().map(|row| row.as_bytes().to_vec()).collect();
+        if symbols.iter().all(|row| row.is_empty()) {
+            return Err(ForestError::Empty);
+        }
+        let expected = symbols[0].len();
+        if let Some((line, row)) = symbols
+            .iter()
+            .enumerate()
+            .find(|(_, row)| row.len() != expected)
+        {
+            return Err(ForestError::Ragged {
+                line: line + 1,
+                width: row.len(),
+                expected,
+            });
+        }
+        let matrix = symbols
+            .iter()
+            .enumerate()
+            .map(|(line, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(|(column, c)| {
+                        legend.index(*c).ok_or(ForestError::UnknownTerrain {
+                            line: line + 1,
+                            column: column + 1,
+                            symbol: *c as char,
+                        })
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Forest {
+            matrix,
+            symbols,
+            legend,
+        })
+    }
+
+    pub fn legend(&self) -> &Legend {
+        &self.legend
+    }
+
+    // Total cost of the lots hit by a slope, the starting lot is free
+    pub fn path_cost(&self, right_step: i32, down_step: i32) -> u32 {
+        self.iter(right_step, down_step)
+            .map(|terrain| terrain.cost)
+            .sum()
+    }
+
+    pub fn iter(&self, right_step: i32, down_step: i32) -> ForestIter<'_> {
+        ForestIter {
+            forest: self,
+            right_step,
+            down_step,
+            current_row: 0,
+            current_col: 0,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.matrix[0].len()
+    }
+
+    pub fn height(&self) -> usize {
+        self.matrix.len()
+    }
+
+    // `col` is not wrapped here, callers deal with the repeating pattern themselves
+    pub fn get(&self, row: usize, col: usize) -> Option<&Terrain> {
+        let index = self.matrix.get(row).and_then(|r| r.get(col))?;
+        Some(&self.legend.terrains[*index])
+    }
+
+    // The character the input has for a lot
+    pub fn symbol(&self, row: usize, col: usize) -> Option<char> {
+        let symbol = self.symbols.get(row).and_then(|r| r.get(col))?;
+        Some(*symbol as char)
+    }
+}
+
+#[derive(Debug)]
+pub struct ForestIter<'a> {
+    forest: &'a Forest,
+    right_step: i32,
+    down_step: i32,
+    current_row: i32,
+    current_col: i32,
+}
+
+impl<'a> ForestIter<'a> {
+    fn get(&self) -> &'a Terrain {
+        let index = self.forest.matrix[self.current_row as usize][self.current_col as usize];
+        &self.forest.legend.terrains[index]
+    }
+
+    // (row, column) of the lot last returned by `next`, column already wrapped
+    pub fn position(&self) -> (usize, usize) {
+        (self.current_row as usize, self.current_col as usize)
+    }
+
+    // Columns wrap both ways, a slope that doesn't go down never gets anywhere so it ends
+    // straight away
+    fn step(&mut self) -> Option<()> {
+        if self.down_step <= 0 {
+            return None;
+        }
+        let width = self.forest.matrix[0].len() as i32;
+        let new_col = (self.current_col + self.right_step).rem_euclid(width);
+        let new_row = self.current_row + self.down_step;
+        if new_row as usize >= self.forest.matrix.len() {
+            return None;
+        }
+        self.current_col = new_col;
+        self.current_row = new_row;
+        Some(())
+    }
+}
+
+impl<'a> Iterator for ForestIter<'a> {
+    type Item = &'a Terrain;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.step()?;
+        Some(self.get())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legend_costs() {
+        let legend = Legend::default().with('*', "snow", 2).with('^', "rock", 10);
+        let forest = Forest::with_legend("..*^\n*.#^\n^^.*\n.#*.", legend).unwrap();
+        assert_eq!(forest.path_cost(1, 1), 0);
+        assert_eq!(forest.path_cost(3, 1), 10 + 1);
+        assert_eq!(forest.legend().terrain('^').unwrap().name, "rock");
+        assert_eq!(forest.symbol(0, 3), Some('^'));
+    }
+
+    #[test]
+    fn any_slope() {
+        let forest = Forest::new("...\n..#\n.#.");
+        assert_eq!(forest.path_cost(-1, 1), 2);
+        assert_eq!(forest.path_cost(-4, 1), 2);
+        assert_eq!(forest.path_cost(1, 1), 0);
+        assert_eq!(forest.iter(1, 0).count(), 0);
+        assert_eq!(forest.iter(1, -1).count(), 0);
+    }
+
+    #[test]
+    fn legend_default_counts_trees() {
+        let forest = Forest::new("..#\n.#.\n#..");
+        assert_eq!(forest.path_cost(1, 1), 1);
+    }
+
+    #[test]
+    fn symbols_share_terrain() {
+        let legend = Legend::default().with('T', "tree", 1).with('~', "bog", 3);
+        assert_eq!(legend.terrains().len(), 3);
+        assert_eq!(legend.terrain('T'), legend.terrain('#'));
+        let forest = Forest::with_legend(".T\n#~\n~T", legend).unwrap();
+        assert_eq!(forest.path_cost(1, 2), 1);
+        assert_eq!(forest.path_cost(0, 1), 1 + 3);
+        assert_eq!(forest.get(0, 1).unwrap().name, "tree");
+
+        // a repeated symbol moves to the new terrain
+        let legend = Legend::default().with('#', "rock", 5);
+        assert_eq!(legend.terrain('#').unwrap().cost, 5);
+        assert_eq!(legend.terrain('*'), None);
+    }
+
+    #[test]
+    fn unknown_terrain() {
+        assert_eq!(
+            Forest::with_legend("..\n.~", Legend::default()).unwrap_err(),
+            ForestError::UnknownTerrain {
+                line: 2,
+                column: 2,
+                symbol: '~'
+            }
+        );
+    }
+
+    #[test]
+    fn empty_forest() {
+        assert_eq!(
+            Forest::with_legend("", Legend::default()).unwrap_err(),
+            ForestError::Empty
+        );
+        assert_eq!(
+            Forest::with_legend("\n\n", Legend::default()).unwrap_err(),
+            ForestError::Empty
+        );
+        assert_eq!(
+            Forest::with_legend("\n..", Legend::default()).unwrap_err(),
+            ForestError::Ragged {
+                line: 2,
+                width: 2,
+                expected: 0
+            }
+        );
+    }
+
+    #[test]
+    fn ragged_forest() {
+        let err = Forest::with_legend("..#\n.#\n#..", Legend::default()).unwrap_err();
+        assert_eq!(
+            err,
+            ForestError::Ragged {
+                line: 2,
+                width: 2,
+                expected: 3
+            }
+        );
+        assert_eq!(err.to_string(), "line 2 is 2 lots wide, expected 3");
+    }
+}
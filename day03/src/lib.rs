@@ -0,0 +1,3 @@
+pub mod forest;
+pub mod render;
+pub mod route;
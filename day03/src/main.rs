@@ -1,4 +1,6 @@
-use std::fs::read_to_string;
+use std::{env, fs::read_to_string, fs::File};
+
+use day03::{forest, render};
 
 const PATHS: &[(i32, i32)] = &[(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
 
@@ -12,16 +14,31 @@ fn main() {
     println!("Part2:");
     let product = product_of_trees_in_paths(&input, PATHS);
     println!("product is: {}", product);
+
+    // `--render` draws the part 1 run in the terminal, `--ppm <file>` saves it as an image
+    let args: Vec<String> = env::args().collect();
+    let forest = forest::Forest::new(&input);
+    let run = render::PathRender::new(&forest, 3, 1);
+    if args.iter().any(|a| a == "--render") {
+        print!("{}", run.to_ansi());
+    }
+    if let Some(pos) = args.iter().position(|a| a == "--ppm") {
+        let path = args.get(pos + 1).expect("--ppm needs a file name");
+        let file = File::create(path).expect("error creating image file");
+        run.write_ppm(file, 4).expect("error writing image file");
+    }
 }
 
 fn count_trees_with_slope(forest: &str, right: i32, down: i32) -> usize {
     count_trees(&forest::Forest::new(forest), right, down)
 }
 
+// Trees are whatever terrain the legend gives '#', whatever it's called
 fn count_trees(forest: &forest::Forest, right: i32, down: i32) -> usize {
+    let tree = forest.legend().terrain('#');
     forest
         .iter(right, down)
-        .filter(|item| **item == forest::Lot::Tree)
+        .filter(|lot| Some(*lot) == tree)
         .count()
 }
 
@@ -33,92 +50,6 @@ fn product_of_trees_in_paths(forest: &str, paths: &[(i32, i32)]) -> usize {
         .product()
 }
 
-mod forest {
-    #[derive(Debug, PartialEq, Eq)]
-    pub enum Lot {
-        Open,
-        Tree,
-    }
-
-    #[derive(Debug)]
-    pub struct Forest {
-        matrix: Vec<Vec<Lot>>,
-    }
-
-    impl Forest {
-        // Should probably return Result<Forest>
-        pub fn new(s: &str) -> Forest {
-            let matrix = s
-                .lines()
-                .map(|line| {
-                    let mut row = Vec::new();
-                    for c in line.as_bytes() {
-                        match c {
-                            b'.' => row.push(Lot::Open),
-                            b'#' => row.push(Lot::Tree),
-                            _ => panic!("Bad Forest input! {}", c),
-                        }
-                    }
-                    row
-                })
-                .collect();
-            Forest { matrix }
-        }
-
-        pub fn iter(&self, right_step: i32, down_step: i32) -> ForestIter {
-            ForestIter {
-                forest: self,
-                right_step,
-                down_step,
-                current_row: 0,
-                current_col: 0,
-            }
-        }
-    }
-
-    #[derive(Debug)]
-    pub struct ForestIter<'a> {
-        forest: &'a Forest,
-        right_step: i32,
-        down_step: i32,
-        current_row: i32,
-        current_col: i32,
-    }
-
-    impl<'a> ForestIter<'a> {
-        fn get(&self) -> &'a Lot {
-            &self.forest.matrix[self.current_row as usize][self.current_col as usize]
-        }
-
-        fn step(&mut self) -> Option<()> {
-            let width = self.forest.matrix[0].len() as i32;
-            let mut new_col = self.current_col + self.right_step;
-            if new_col >= width {
-                new_col %= width;
-            } else if new_col < 0 {
-                //unneeded for positive rightward step
-                unreachable!();
-            }
-            let new_row = self.current_row + self.down_step;
-            if new_row as usize >= self.forest.matrix.len() {
-                return None;
-            }
-            self.current_col = new_col;
-            self.current_row = new_row;
-            Some(())
-        }
-    }
-
-    impl<'a> Iterator for ForestIter<'a> {
-        type Item = &'a Lot;
-
-        fn next(&mut self) -> Option<Self::Item> {
-            self.step()?;
-            Some(self.get())
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -152,16 +83,16 @@ mod tests {
 .#..#...#.#";
         let forest = forest::Forest::new(input);
         let mut iter = forest.iter(3, 1);
-        assert_eq!(*iter.next().unwrap(), forest::Lot::Open);
-        assert_eq!(*iter.next().unwrap(), forest::Lot::Tree);
-        assert_eq!(*iter.next().unwrap(), forest::Lot::Open);
-        assert_eq!(*iter.next().unwrap(), forest::Lot::Tree);
-        assert_eq!(*iter.next().unwrap(), forest::Lot::Tree);
-        assert_eq!(*iter.next().unwrap(), forest::Lot::Open);
-        assert_eq!(*iter.next().unwrap(), forest::Lot::Tree);
-        assert_eq!(*iter.next().unwrap(), forest::Lot::Tree);
-        assert_eq!(*iter.next().unwrap(), forest::Lot::Tree);
-        assert_eq!(*iter.next().unwrap(), forest::Lot::Tree);
+        assert_eq!(iter.next().unwrap().name, "open");
+        assert_eq!(iter.next().unwrap().name, "tree");
+        assert_eq!(iter.next().unwrap().name, "open");
+        assert_eq!(iter.next().unwrap().name, "tree");
+        assert_eq!(iter.next().unwrap().name, "tree");
+        assert_eq!(iter.next().unwrap().name, "open");
+        assert_eq!(iter.next().unwrap().name, "tree");
+        assert_eq!(iter.next().unwrap().name, "tree");
+        assert_eq!(iter.next().unwrap().name, "tree");
+        assert_eq!(iter.next().unwrap().name, "tree");
         assert!(iter.next().is_none());
     }
     #[test]
@@ -179,4 +110,13 @@ mod tests {
 .#..#...#.#";
         assert_eq!(product_of_trees_in_paths(input, PATHS), 336);
     }
+    #[test]
+    fn trees_by_symbol() {
+        let legend = forest::Legend::new()
+            .with('.', "snow", 0)
+            .with('#', "pine", 2);
+        let forest = forest::Forest::with_legend("..#\n.#.\n#..", legend).unwrap();
+        assert_eq!(count_trees(&forest, 1, 1), 1);
+        assert_eq!(count_trees(&forest, 2, 1), 0);
+    }
 }
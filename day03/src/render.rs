@@ -0,0 +1,216 @@
+use std::{collections::HashSet, fmt, io};
+
+use crate::{forest::Forest, route::Route};
+
+const ANSI_RESET: &str = "\x1b[0m";
+const ANSI_OPEN_HIT: &str = "\x1b[1;32m";
+const ANSI_TREE_HIT: &str = "\x1b[1;31m";
+
+const PPM_OPEN: [u8; 3] = [0xf0, 0xf0, 0xf0];
+const PPM_TREE: [u8; 3] = [0x1e, 0x5a, 0x1e];
+const PPM_OPEN_HIT: [u8; 3] = [0x32, 0x82, 0xf0];
+const PPM_TREE_HIT: [u8; 3] = [0xdc, 0x28, 0x28];
+
+// Forest with the lots hit by one slope marked, like the drawings in the puzzle statement
+// The starting lot isn't marked, same as `ForestIter` never yielding it
+#[derive(Debug)]
+pub struct PathRender<'a> {
+    forest: &'a Forest,
+    hits: HashSet<(usize, usize)>,
+}
+
+impl<'a> PathRender<'a> {
+    pub fn new(forest: &'a Forest, right_step: i32, down_step: i32) -> PathRender<'a> {
+        let mut iter = forest.iter(right_step, down_step);
+        let mut hits = HashSet::new();
+        while iter.next().is_some() {
+            hits.insert(iter.position());
+        }
+        PathRender { forest, hits }
+    }
+
+    pub fn for_route(forest: &'a Forest, route: &Route) -> PathRender<'a> {
+        let hits = route.path.iter().skip(1).cloned().collect();
+        PathRender { forest, hits }
+    }
+
+    // `row` and `col` always come from the forest's own width and height
+    fn cost(&self, row: usize, col: usize) -> u32 {
+        self.forest.get(row, col).map_or(0, |terrain| terrain.cost)
+    }
+
+    // `Some(true)` for a hit on a lot that costs something to pass, `Some(false)` for a hit
+    // on a free one and `None` if the lot wasn't hit
+    fn hit(&self, row: usize, col: usize) -> Option<bool> {
+        if !self.hits.contains(&(row, col)) {
+            return None;
+        }
+        Some(self.cost(row, col) > 0)
+    }
+
+    // Hits are drawn as `X` where the lot costs something to pass and `O` where it's free,
+    // everything else as it is in the input
+    fn cell(&self, row: usize, col: usize) -> char {
+        match self.hit(row, col) {
+            Some(true) => 'X',
+            Some(false) => 'O',
+            None => self.forest.symbol(row, col).unwrap_or('?'),
+        }
+    }
+
+    // Same as `Display` but with hits coloured for a terminal
+    pub fn to_ansi(&self) -> String {
+        let mut out = String::new();
+        for row in 0..self.forest.height() {
+            for col in 0..self.forest.width() {
+                let c = self.cell(row, col);
+                match self.hit(row, col) {
+                    Some(false) => out.push_str(&format!("{}{}{}", ANSI_OPEN_HIT, c, ANSI_RESET)),
+                    Some(true) => out.push_str(&format!("{}{}{}", ANSI_TREE_HIT, c, ANSI_RESET)),
+                    None => out.push(c),
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    // Binary PPM (P6), every lot is drawn as a `scale` x `scale` square
+    // Lots that cost something to pass are drawn like trees, free ones like open ground
+    pub fn write_ppm<W: io::Write>(&self, mut w: W, scale: usize) -> io::Result<()> {
+        let width = self.forest.width() * scale;
+        let height = self.forest.height() * scale;
+        write!(w, "P6\n{} {}\n255\n", width, height)?;
+        for row in 0..self.forest.height() {
+            let line: Vec<u8> = (0..self.forest.width())
+                .flat_map(|col| {
+                    let pixel = match self.hit(row, col) {
+                        Some(false) => PPM_OPEN_HIT,
+                        Some(true) => PPM_TREE_HIT,
+                        None if self.cost(row, col) > 0 => PPM_TREE,
+                        None => PPM_OPEN,
+                    };
+                    pixel
+                        .iter()
+                        .cycle()
+                        .take(3 * scale)
+                        .cloned()
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            for _ in 0..scale {
+                w.write_all(&line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Display for PathRender<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.forest.height() {
+            let line: String = (0..self.forest.width())
+                .map(|col| self.cell(row, col))
+                .collect();
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forest::Legend;
+
+    const INPUT: &str = "..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#";
+
+    #[test]
+    fn render_text() {
+        let forest = Forest::new(INPUT);
+        let render = PathRender::new(&forest, 3, 1);
+        assert_eq!(
+            render.to_string(),
+            "..##.......
+#..O#...#..
+.#....X..#.
+..#.#...#O#
+.X...##..#.
+..#.X#.....
+.#.#.#.O..#
+.#........X
+#.X#...#...
+#...#X....#
+.#..#...X.#
+"
+        );
+    }
+
+    #[test]
+    fn render_custom_legend() {
+        let legend = Legend::default().with('*', "snow", 0).with('^', "rock", 5);
+        let forest = Forest::with_legend("*..^\n.^*.\n#.^*", legend).unwrap();
+        let render = PathRender::new(&forest, 1, 1);
+        assert_eq!(render.to_string(), "*..^\n.X*.\n#.X*\n");
+    }
+
+    #[test]
+    fn render_route() {
+        let forest = Forest::new(".#\n#.\n.#");
+        let route = crate::route::cheapest_route(&forest, &[(-1, 1)]).unwrap();
+        let render = PathRender::for_route(&forest, &route);
+        assert_eq!(render.to_string(), ".#\n#O\nO#\n");
+        let render = PathRender::new(&forest, -1, 1);
+        assert_eq!(render.to_string(), ".#\n#O\nO#\n");
+        let render = PathRender::new(&forest, 1, 0);
+        assert_eq!(render.to_string(), ".#\n#.\n.#\n");
+    }
+
+    #[test]
+    fn render_ansi() {
+        let forest = Forest::new(INPUT);
+        let render = PathRender::new(&forest, 3, 1);
+        let ansi = render.to_ansi();
+        assert_eq!(ansi.matches(ANSI_TREE_HIT).count(), 7);
+        assert_eq!(ansi.matches(ANSI_OPEN_HIT).count(), 3);
+    }
+
+    #[test]
+    fn render_hit_symbols() {
+        // 'X' and 'O' in the input are only lots, not hits
+        let legend = Legend::default().with('X', "rock", 5).with('O', "pond", 0);
+        let forest = Forest::with_legend(".XO\n.#.\nX.O", legend).unwrap();
+        let render = PathRender::new(&forest, 1, 1);
+        let ansi = render.to_ansi();
+        assert_eq!(ansi.matches(ANSI_TREE_HIT).count(), 1);
+        assert_eq!(ansi.matches(ANSI_OPEN_HIT).count(), 1);
+        let mut out = Vec::new();
+        render.write_ppm(&mut out, 1).unwrap();
+        let pixels = &out[b"P6\n3 3\n255\n".len()..];
+        assert_eq!(&pixels[3..6], &PPM_TREE);
+        assert_eq!(&pixels[6..9], &PPM_OPEN);
+        assert_eq!(&pixels[12..15], &PPM_TREE_HIT);
+        assert_eq!(&pixels[24..27], &PPM_OPEN_HIT);
+    }
+
+    #[test]
+    fn render_ppm() {
+        let forest = Forest::new(INPUT);
+        let render = PathRender::new(&forest, 3, 1);
+        let mut out = Vec::new();
+        render.write_ppm(&mut out, 2).unwrap();
+        let header = b"P6\n22 22\n255\n";
+        assert!(out.starts_with(header));
+        assert_eq!(out.len(), header.len() + 22 * 22 * 3);
+    }
+}
@@ -0,0 +1,116 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
+use crate::forest::Forest;
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Route {
+    // (row, column) of every lot visited, starting with the top left one
+    pub path: Vec<(usize, usize)>,
+    pub cost: u32,
+}
+
+// Cheapest route from the top left lot until a move takes the toboggan off the bottom
+// Every move is a (right, down) step, columns wrap around like they do for `ForestIter`
+// The starting lot is free, same as `Forest::path_cost`
+// Returns `None` if none of the `moves` can ever leave the forest
+pub fn cheapest_route(forest: &Forest, moves: &[(i32, i32)]) -> Option<Route> {
+    let width = forest.width() as i64;
+    let height = forest.height() as i64;
+
+    let mut best: HashMap<(usize, usize), u32> = HashMap::new();
+    let mut came_from: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    best.insert((0, 0), 0);
+    queue.push(Reverse((0, 0usize, 0usize)));
+
+    while let Some(Reverse((cost, row, col))) = queue.pop() {
+        if best.get(&(row, col)).is_some_and(|&c| c < cost) {
+            continue;
+        }
+        // first lot popped that can leave the forest is the cheapest finish,
+        // leaving is free so nothing still queued can beat it
+        if moves
+            .iter()
+            .any(|&(_, down)| row as i64 + down as i64 >= height)
+        {
+            let mut path = vec![(row, col)];
+            while let Some(prev) = came_from.get(path.last().unwrap()) {
+                path.push(*prev);
+            }
+            path.reverse();
+            return Some(Route { path, cost });
+        }
+        for &(right, down) in moves {
+            let new_row = row as i64 + down as i64;
+            if new_row < 0 {
+                continue;
+            }
+            let next = (
+                new_row as usize,
+                (col as i64 + right as i64).rem_euclid(width) as usize,
+            );
+            let new_cost = cost + forest.get(next.0, next.1).unwrap().cost;
+            if best.get(&next).is_none_or(|&c| new_cost < c) {
+                best.insert(next, new_cost);
+                came_from.insert(next, (row, col));
+                queue.push(Reverse((new_cost, next.0, next.1)));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#";
+
+    #[test]
+    fn single_slope_matches_iter() {
+        let forest = Forest::new(INPUT);
+        let route = cheapest_route(&forest, &[(3, 1)]).unwrap();
+        assert_eq!(route.cost, 7);
+        assert_eq!(route.path.len(), 11);
+        assert_eq!(route.path[0], (0, 0));
+        assert_eq!(route.path[10], (10, 8));
+    }
+
+    #[test]
+    fn cheapest_of_several_moves() {
+        let forest = Forest::new(INPUT);
+        let route = cheapest_route(&forest, &[(-1, 1), (0, 1), (1, 1)]).unwrap();
+        assert_eq!(route.cost, 0);
+        for (row, col) in &route.path {
+            assert_eq!(forest.symbol(*row, *col), Some('.'));
+        }
+    }
+
+    #[test]
+    fn wraps_around() {
+        let forest = Forest::new(".#\n#.\n.#");
+        let route = cheapest_route(&forest, &[(-1, 1)]).unwrap();
+        assert_eq!(route.path, vec![(0, 0), (1, 1), (2, 0)]);
+        assert_eq!(route.cost, 0);
+    }
+
+    #[test]
+    fn no_way_out() {
+        let forest = Forest::new("..\n..");
+        assert!(cheapest_route(&forest, &[(1, 0)]).is_none());
+        assert!(cheapest_route(&forest, &[]).is_none());
+    }
+}
@@ -0,0 +1,166 @@
+use std::{fmt, str::FromStr};
+
+// CSS names for the colours hair usually comes in, plus the basics
+const NAMED: &[(&str, Color)] = &[
+    ("black", Color::new(0x00, 0x00, 0x00)),
+    ("white", Color::new(0xff, 0xff, 0xff)),
+    ("gray", Color::new(0x80, 0x80, 0x80)),
+    ("grey", Color::new(0x80, 0x80, 0x80)),
+    ("silver", Color::new(0xc0, 0xc0, 0xc0)),
+    ("red", Color::new(0xff, 0x00, 0x00)),
+    ("green", Color::new(0x00, 0x80, 0x00)),
+    ("blue", Color::new(0x00, 0x00, 0xff)),
+    ("yellow", Color::new(0xff, 0xff, 0x00)),
+    ("orange", Color::new(0xff, 0xa5, 0x00)),
+    ("purple", Color::new(0x80, 0x00, 0x80)),
+    ("pink", Color::new(0xff, 0xc0, 0xcb)),
+    ("brown", Color::new(0xa5, 0x2a, 0x2a)),
+    ("chocolate", Color::new(0xd2, 0x69, 0x1e)),
+    ("sienna", Color::new(0xa0, 0x52, 0x2d)),
+    ("tan", Color::new(0xd2, 0xb4, 0x8c)),
+    ("wheat", Color::new(0xf5, 0xde, 0xb3)),
+    ("gold", Color::new(0xff, 0xd7, 0x00)),
+    ("goldenrod", Color::new(0xda, 0xa5, 0x20)),
+];
+
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ParseColorError;
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("not a #rgb, #rrggbb or named colour")
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
+impl Color {
+    pub const fn new(r: u8, g: u8, b: u8) -> Color {
+        Color { r, g, b }
+    }
+
+    pub fn components(&self) -> (u8, u8, u8) {
+        (self.r, self.g, self.b)
+    }
+
+    pub fn named(name: &str) -> Option<Color> {
+        NAMED
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, color)| *color)
+    }
+
+    // Only for an exact match, see `nearest_name` otherwise
+    pub fn name(&self) -> Option<&'static str> {
+        NAMED.iter().find(|(_, c)| c == self).map(|(n, _)| *n)
+    }
+
+    pub fn nearest_name(&self) -> &'static str {
+        NAMED
+            .iter()
+            .min_by(|(_, a), (_, b)| {
+                self.redmean_distance(a)
+                    .partial_cmp(&self.redmean_distance(b))
+                    .unwrap()
+            })
+            .map(|(n, _)| *n)
+            .unwrap()
+    }
+
+    // Straight line distance in RGB space
+    pub fn distance(&self, other: &Color) -> f64 {
+        let dr = self.r as f64 - other.r as f64;
+        let dg = self.g as f64 - other.g as f64;
+        let db = self.b as f64 - other.b as f64;
+        (dr * dr + dg * dg + db * db).sqrt()
+    }
+
+    // RGB distance weighted by how red the colours are, a cheap stand-in for perceived difference
+    pub fn redmean_distance(&self, other: &Color) -> f64 {
+        let mean_r = (self.r as f64 + other.r as f64) / 2.0;
+        let dr = self.r as f64 - other.r as f64;
+        let dg = self.g as f64 - other.g as f64;
+        let db = self.b as f64 - other.b as f64;
+        ((2.0 + mean_r / 256.0) * dr * dr
+            + 4.0 * dg * dg
+            + (2.0 + (255.0 - mean_r) / 256.0) * db * db)
+            .sqrt()
+    }
+}
+
+fn hex_digit(c: u8) -> Result<u8, ParseColorError> {
+    (c as char)
+        .to_digit(16)
+        .map(|d| d as u8)
+        .ok_or(ParseColorError)
+}
+
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    // "#rgb", "#rrggbb" in either case, or a name like "brown"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = match s.strip_prefix('#') {
+            Some(hex) => hex.as_bytes(),
+            None => return Color::named(s).ok_or(ParseColorError),
+        };
+        let digits = hex
+            .iter()
+            .map(|c| hex_digit(*c))
+            .collect::<Result<Vec<_>, _>>()?;
+        match digits.as_slice() {
+            [r, g, b] => Ok(Color::new(r * 17, g * 17, b * 17)),
+            [r1, r2, g1, g2, b1, b2] => Ok(Color::new(r1 * 16 + r2, g1 * 16 + g2, b1 * 16 + b2)),
+            _ => Err(ParseColorError),
+        }
+    }
+}
+
+// Always the long lower case form, which is what the puzzle accepts
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let color = Color::new(0x62, 0x3a, 0x2f);
+        assert_eq!("#623a2f".parse(), Ok(color));
+        assert_eq!("#623A2F".parse(), Ok(color));
+        assert_eq!("#fa0".parse(), Ok(Color::new(0xff, 0xaa, 0x00)));
+        assert_eq!("Brown".parse(), Ok(Color::new(0xa5, 0x2a, 0x2a)));
+        assert_eq!("#623a2".parse::<Color>(), Err(ParseColorError));
+        assert_eq!("#623a2g".parse::<Color>(), Err(ParseColorError));
+        assert_eq!("623a2f".parse::<Color>(), Err(ParseColorError));
+        assert_eq!("mauve".parse::<Color>(), Err(ParseColorError));
+        assert_eq!(color.to_string(), "#623a2f");
+        assert_eq!(color.components(), (0x62, 0x3a, 0x2f));
+    }
+
+    #[test]
+    fn names_and_distance() {
+        assert_eq!(Color::new(0, 0, 0).name(), Some("black"));
+        assert_eq!(Color::new(0x62, 0x3a, 0x2f).name(), None);
+        assert_eq!(Color::new(0x9a, 0x30, 0x2a).nearest_name(), "brown");
+        assert_eq!(Color::new(0xfe, 0xfe, 0xfd).nearest_name(), "white");
+        let black = Color::new(0, 0, 0);
+        assert_eq!(black.distance(&Color::new(3, 4, 0)), 5.0);
+        assert_eq!(black.redmean_distance(&black), 0.0);
+        assert!(
+            black.redmean_distance(&Color::new(0, 10, 0))
+                > black.redmean_distance(&Color::new(0, 0, 10))
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use serde_json::{Map, Value};
+
+use crate::passport::{Field, Record};
+
+// Blank line separated records, same as the puzzle input
+pub fn to_batch<T: Record>(passports: &[T]) -> String {
+    passports
+        .iter()
+        .map(|p| format!("{}\n", p))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// An array of objects keyed by field, e.g. `{"byr": "1937", ...}`, extra keys included
+pub fn to_json<T: Record>(passports: &[T]) -> String {
+    let records = passports
+        .iter()
+        .map(|p| {
+            let mut object: Map<String, Value> = p
+                .entries()
+                .into_iter()
+                .map(|(field, value)| (field.key().to_string(), Value::String(value)))
+                .collect();
+            for (key, value) in p.extra() {
+                object.insert(key.clone(), Value::String(value.clone()));
+            }
+            Value::Object(object)
+        })
+        .collect();
+    serde_json::to_string_pretty(&Value::Array(records)).unwrap()
+}
+
+// One column per field in `Field::ALL` order, missing values are left empty
+// Extra keys differ per record so they have no column
+pub fn to_csv<T: Record>(passports: &[T]) -> String {
+    let mut out = Field::ALL
+        .iter()
+        .map(|f| f.key())
+        .collect::<Vec<_>>()
+        .join(",");
+    out.push('\n');
+    for passport in passports {
+        let entries = passport.entries();
+        let row = Field::ALL
+            .iter()
+            .map(|field| {
+                entries
+                    .iter()
+                    .find(|(f, _)| f == field)
+                    .map_or(String::new(), |(_, value)| csv_escape(value))
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        out.push_str(&row);
+        out.push('\n');
+    }
+    out
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::passport::{Passport, ValidatedPassport};
+
+    const INPUT: &str = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183cm
+
+hcl:#ae17e1 iyr:2013
+eyr:2024
+ecl:brn pid:760753108 byr:1931
+hgt:179cm";
+
+    fn passports() -> Vec<Passport> {
+        INPUT.split("\n\n").map(|s| s.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn batch_round_trip() {
+        let batch = to_batch(&passports());
+        assert_eq!(
+            batch,
+            "byr:1937 iyr:2017 eyr:2020 hgt:183cm hcl:#fffffd ecl:gry pid:860033327 cid:147
+
+byr:1931 iyr:2013 eyr:2024 hgt:179cm hcl:#ae17e1 ecl:brn pid:760753108
+"
+        );
+        let reparsed: Vec<Passport> = batch.split("\n\n").map(|s| s.parse().unwrap()).collect();
+        assert_eq!(reparsed, passports());
+        let validated: Vec<ValidatedPassport> =
+            batch.split("\n\n").map(|s| s.parse().unwrap()).collect();
+        assert_eq!(to_batch(&validated), batch);
+    }
+
+    #[test]
+    fn json() {
+        let json: Value = serde_json::from_str(&to_json(&passports())).unwrap();
+        assert_eq!(json[0]["hgt"], "183cm");
+        assert_eq!(json[0]["cid"], "147");
+        assert!(json[1].get("cid").is_none());
+
+        let options = crate::passport::ParseOptions {
+            unknown_keys: crate::passport::UnknownKeys::Collect,
+            ..Default::default()
+        };
+        let passport = Passport::parse_with(&format!("{} foo:bar", passports()[1]), &options);
+        let json: Value = serde_json::from_str(&to_json(&[passport.unwrap()])).unwrap();
+        assert_eq!(json[0]["foo"], "bar");
+    }
+
+    #[test]
+    fn csv() {
+        assert_eq!(
+            to_csv(&passports()),
+            "byr,iyr,eyr,hgt,hcl,ecl,pid,cid
+1937,2017,2020,183cm,#fffffd,gry,860033327,147
+1931,2013,2024,179cm,#ae17e1,brn,760753108,
+"
+        );
+        assert_eq!(csv_escape("5'11\""), "\"5'11\"\"\"");
+    }
+}
@@ -0,0 +1,228 @@
+use std::{cmp::Ordering, convert::TryFrom, fmt, hash, str::FromStr};
+
+use serde::Deserialize;
+
+const MICROMETRES_PER_MM: i64 = 1_000;
+const MICROMETRES_PER_INCH: i64 = 25_400;
+
+// The units written straight after the number, feet and inches are written 5'11" instead
+pub const UNITS: &[&str] = &["cm", "in", "mm", "m"];
+
+// A height in whatever unit it was written in
+// Comparisons go through `micrometres`, so `Cm(190) == Mm(1900)`
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Height {
+    Cm(i32),
+    Inch(i32),
+    Mm(i32),
+    // Written in metres, kept in millimetres so "1.83m" stays exact
+    Metre(i32),
+    // 5'11"
+    FeetInches(i32, i32),
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum HeightError {
+    NotANumber,
+    BadUnit,
+    // Too big to hold, or 12 inches or more after the feet
+    OutOfRange,
+}
+
+impl fmt::Display for HeightError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeightError::NotANumber => f.write_str("height is not a number"),
+            HeightError::BadUnit => f.write_str("height has an unknown unit"),
+            HeightError::OutOfRange => f.write_str("height is out of range"),
+        }
+    }
+}
+
+impl std::error::Error for HeightError {}
+
+impl Height {
+    // The canonical unit, every unit we read is a whole number of micrometres
+    pub fn micrometres(&self) -> i64 {
+        match *self {
+            Height::Cm(cm) => cm as i64 * 10 * MICROMETRES_PER_MM,
+            Height::Inch(inch) => inch as i64 * MICROMETRES_PER_INCH,
+            Height::Mm(mm) | Height::Metre(mm) => mm as i64 * MICROMETRES_PER_MM,
+            Height::FeetInches(feet, inch) => {
+                (feet as i64 * 12 + inch as i64) * MICROMETRES_PER_INCH
+            }
+        }
+    }
+
+    pub fn cm(&self) -> f64 {
+        self.micrometres() as f64 / 10_000.0
+    }
+
+    // The unit as it appears in the input, feet and inches share "ft"
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Height::Cm(_) => "cm",
+            Height::Inch(_) => "in",
+            Height::Mm(_) => "mm",
+            Height::Metre(_) => "m",
+            Height::FeetInches(_, _) => "ft",
+        }
+    }
+}
+
+fn parse_number(s: &str) -> Result<i32, HeightError> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(HeightError::NotANumber);
+    }
+    s.parse().map_err(|_| HeightError::NotANumber)
+}
+
+// "1.83" -> 1830
+fn parse_metres(s: &str) -> Result<i32, HeightError> {
+    let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+    if fraction.len() > 3 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(HeightError::NotANumber);
+    }
+    let fraction = format!("{:0<3}", fraction);
+    parse_number(whole)?
+        .checked_mul(1000)
+        .and_then(|mm| mm.checked_add(fraction.parse().unwrap()))
+        .ok_or(HeightError::OutOfRange)
+}
+
+impl FromStr for Height {
+    type Err = HeightError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((feet, inch)) = s.split_once('\'') {
+            let inch = inch.strip_suffix('"').unwrap_or(inch);
+            let inch = if inch.is_empty() {
+                0
+            } else {
+                parse_number(inch)?
+            };
+            if inch >= 12 {
+                return Err(HeightError::OutOfRange);
+            }
+            return Ok(Height::FeetInches(parse_number(feet)?, inch));
+        }
+        let at = s
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(s.len());
+        let (value, unit) = s.split_at(at);
+        match unit {
+            "cm" => Ok(Height::Cm(parse_number(value)?)),
+            "in" => Ok(Height::Inch(parse_number(value)?)),
+            "mm" => Ok(Height::Mm(parse_number(value)?)),
+            "m" => Ok(Height::Metre(parse_metres(value)?)),
+            _ => Err(HeightError::BadUnit),
+        }
+    }
+}
+
+impl TryFrom<String> for Height {
+    type Error = HeightError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for Height {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Height::Cm(cm) => write!(f, "{}cm", cm),
+            Height::Inch(inch) => write!(f, "{}in", inch),
+            Height::Mm(mm) => write!(f, "{}mm", mm),
+            Height::Metre(mm) => {
+                let fraction = format!("{:03}", mm % 1000);
+                let fraction = fraction.trim_end_matches('0');
+                if fraction.is_empty() {
+                    write!(f, "{}m", mm / 1000)
+                } else {
+                    write!(f, "{}.{}m", mm / 1000, fraction)
+                }
+            }
+            Height::FeetInches(feet, inch) => write!(f, "{}'{}\"", feet, inch),
+        }
+    }
+}
+
+impl PartialEq for Height {
+    fn eq(&self, other: &Self) -> bool {
+        self.micrometres() == other.micrometres()
+    }
+}
+
+impl Eq for Height {}
+
+impl PartialOrd for Height {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Height {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.micrometres().cmp(&other.micrometres())
+    }
+}
+
+impl hash::Hash for Height {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.micrometres().hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_units() {
+        assert_eq!("183cm".parse(), Ok(Height::Cm(183)));
+        assert_eq!("74in".parse(), Ok(Height::Inch(74)));
+        assert_eq!("1830mm".parse(), Ok(Height::Mm(1830)));
+        assert_eq!("1.83m".parse(), Ok(Height::Metre(1830)));
+        assert_eq!("2m".parse(), Ok(Height::Metre(2000)));
+        assert_eq!("6'1\"".parse(), Ok(Height::FeetInches(6, 1)));
+        assert_eq!("6'".parse(), Ok(Height::FeetInches(6, 0)));
+        assert_eq!("190".parse::<Height>(), Err(HeightError::BadUnit));
+        assert_eq!("190ft".parse::<Height>(), Err(HeightError::BadUnit));
+        assert_eq!("cm".parse::<Height>(), Err(HeightError::NotANumber));
+        assert_eq!("1.8in".parse::<Height>(), Err(HeightError::NotANumber));
+        assert_eq!("1.8305m".parse::<Height>(), Err(HeightError::NotANumber));
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert_eq!("2147483m".parse(), Ok(Height::Metre(2_147_483_000)));
+        assert_eq!("3000000m".parse::<Height>(), Err(HeightError::OutOfRange));
+        assert_eq!(
+            "2147483.648m".parse::<Height>(),
+            Err(HeightError::OutOfRange)
+        );
+        assert_eq!("5'11\"".parse(), Ok(Height::FeetInches(5, 11)));
+        assert_eq!("5'12\"".parse::<Height>(), Err(HeightError::OutOfRange));
+        assert_eq!("5'99\"".parse::<Height>(), Err(HeightError::OutOfRange));
+    }
+
+    #[test]
+    fn compare_across_units() {
+        assert_eq!(Height::Cm(183), Height::Mm(1830));
+        assert_eq!(Height::Cm(183), Height::Metre(1830));
+        assert_eq!(Height::Inch(73), Height::FeetInches(6, 1));
+        assert!(Height::Inch(59) < Height::Cm(150));
+        assert!(Height::Inch(76) > Height::Cm(193));
+        assert_eq!(Height::Inch(1).micrometres(), 25_400);
+        assert!((Height::Inch(10).cm() - 25.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn display_round_trip() {
+        for s in &["183cm", "74in", "1830mm", "1.83m", "2m", "1.805m", "5'11\""] {
+            assert_eq!(s.parse::<Height>().unwrap().to_string(), *s);
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+
+use crate::passport::{Field, PassportError, ValidatedPassport};
+
+// Checks a passport ID for transcription errors, usually through a check digit
+pub trait IdValidator {
+    fn is_valid(&self, id: &str) -> bool;
+}
+
+// Any closure works as a one off validator
+impl<F: Fn(&str) -> bool> IdValidator for F {
+    fn is_valid(&self, id: &str) -> bool {
+        self(id)
+    }
+}
+
+// Last digit is the Luhn check digit of the others
+#[derive(Debug, Clone, Copy)]
+pub struct Luhn;
+
+impl IdValidator for Luhn {
+    fn is_valid(&self, id: &str) -> bool {
+        let digits = match digits(id) {
+            Some(digits) if !digits.is_empty() => digits,
+            _ => return false,
+        };
+        let sum: u32 = digits
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, d)| {
+                if i % 2 == 1 {
+                    let doubled = d * 2;
+                    if doubled > 9 {
+                        doubled - 9
+                    } else {
+                        doubled
+                    }
+                } else {
+                    *d
+                }
+            })
+            .sum();
+        sum.is_multiple_of(10)
+    }
+}
+
+// Last character is the ICAO 9303 check digit of the others
+#[derive(Debug, Clone, Copy)]
+pub struct Icao9303;
+
+// Weights 7, 3, 1 repeating; digits count as themselves, A-Z as 10-35 and the '<' filler as 0
+// `None` if `s` has any other character
+pub fn icao_check_digit(s: &str) -> Option<u32> {
+    let mut sum = 0;
+    for (i, c) in s.chars().enumerate() {
+        let value = match c {
+            '0'..='9' => c as u32 - '0' as u32,
+            'A'..='Z' => c as u32 - 'A' as u32 + 10,
+            '<' => 0,
+            _ => return None,
+        };
+        sum += value * [7, 3, 1][i % 3];
+    }
+    Some(sum % 10)
+}
+
+impl IdValidator for Icao9303 {
+    fn is_valid(&self, id: &str) -> bool {
+        let (body, check) = match split_check(id) {
+            Some(parts) => parts,
+            None => return false,
+        };
+        icao_check_digit(body)
+            .zip(check.to_digit(10))
+            .is_some_and(|(expected, check)| expected == check)
+    }
+}
+
+// Last character is the weighted sum of the other digits mod `modulus`
+// Weights repeat from the left, a check value of 10 is written as 'X'
+#[derive(Debug, Clone)]
+pub struct ModN {
+    pub modulus: u32,
+    pub weights: Vec<u32>,
+}
+
+impl IdValidator for ModN {
+    fn is_valid(&self, id: &str) -> bool {
+        let (body, check) = match split_check(id) {
+            Some(parts) => parts,
+            None => return false,
+        };
+        let check = match check {
+            'X' => 10,
+            c => match c.to_digit(10) {
+                Some(d) => d,
+                None => return false,
+            },
+        };
+        let digits = match digits(body) {
+            Some(digits) => digits,
+            None => return false,
+        };
+        if self.modulus == 0 || self.weights.is_empty() {
+            return false;
+        }
+        // reduced as it goes so large weights or long IDs can't overflow
+        let modulus = u64::from(self.modulus);
+        let sum = digits
+            .iter()
+            .zip(self.weights.iter().cycle())
+            .fold(0, |sum, (d, w)| {
+                (sum + u64::from(*d) * (u64::from(*w) % modulus)) % modulus
+            });
+        sum == u64::from(check)
+    }
+}
+
+fn digits(s: &str) -> Option<Vec<u32>> {
+    s.chars().map(|c| c.to_digit(10)).collect()
+}
+
+fn split_check(id: &str) -> Option<(&str, char)> {
+    let check = id.chars().last()?;
+    Some((&id[..id.len() - check.len_utf8()], check))
+}
+
+// Which validator to use for which country ID (`cid`)
+// Passports from countries without one, or without a `cid`, go to the fallback if there is one
+#[derive(Default)]
+pub struct IdValidators {
+    by_country: HashMap<String, Box<dyn IdValidator>>,
+    fallback: Option<Box<dyn IdValidator>>,
+}
+
+impl IdValidators {
+    pub fn new() -> IdValidators {
+        IdValidators::default()
+    }
+
+    pub fn with_country<V: IdValidator + 'static>(mut self, cid: &str, validator: V) -> Self {
+        self.by_country.insert(cid.to_string(), Box::new(validator));
+        self
+    }
+
+    pub fn with_fallback<V: IdValidator + 'static>(mut self, validator: V) -> Self {
+        self.fallback = Some(Box::new(validator));
+        self
+    }
+
+    pub fn is_valid(&self, cid: Option<&str>, pid: &str) -> bool {
+        let validator = cid
+            .and_then(|cid| self.by_country.get(cid))
+            .or(self.fallback.as_ref());
+        validator.is_none_or(|v| v.is_valid(pid))
+    }
+
+    pub fn check(&self, passport: &ValidatedPassport) -> Result<(), PassportError> {
+        if !self.is_valid(passport.country_id.as_deref(), &passport.passport_id) {
+            return Err(PassportError::BadChecksum(Field::Pid));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn luhn() {
+        assert!(Luhn.is_valid("79927398713"));
+        assert!(!Luhn.is_valid("79927398710"));
+        assert!(Luhn.is_valid("000000000"));
+        assert!(!Luhn.is_valid(""));
+        assert!(!Luhn.is_valid("1234a"));
+    }
+
+    #[test]
+    fn icao() {
+        // passport number from the ICAO 9303 part 4 specimen
+        assert_eq!(icao_check_digit("L898902C3"), Some(6));
+        assert_eq!(icao_check_digit("740812"), Some(2));
+        assert_eq!(icao_check_digit("l898902c3"), None);
+        assert!(Icao9303.is_valid("L898902C36"));
+        assert!(!Icao9303.is_valid("L898902C37"));
+        assert!(!Icao9303.is_valid(""));
+    }
+
+    #[test]
+    fn mod_n() {
+        // ISBN-10 style: weights 1..9, mod 11
+        let isbn = ModN {
+            modulus: 11,
+            weights: (1..=9).collect(),
+        };
+        assert!(isbn.is_valid("0306406152"));
+        assert!(!isbn.is_valid("0306406153"));
+        assert!(isbn.is_valid("080442957X"));
+
+        // the same weights shifted by a multiple of 11, far past what a u32 sum holds
+        let big = ModN {
+            modulus: 11,
+            weights: (1..=9).map(|w| w + 11 * 390_000_000).collect(),
+        };
+        assert!(big.is_valid("0306406152"));
+        assert!(!big.is_valid("0306406153"));
+    }
+
+    #[test]
+    fn per_country() {
+        let validators = IdValidators::new()
+            .with_country("100", Luhn)
+            .with_country("200", |id: &str| id.starts_with('0'))
+            .with_fallback(Icao9303);
+        assert!(validators.is_valid(Some("100"), "799273982"));
+        assert!(!validators.is_valid(Some("100"), "799273988"));
+        assert!(validators.is_valid(Some("200"), "012345678"));
+        assert!(!validators.is_valid(Some("200"), "112345678"));
+        assert!(validators.is_valid(None, "740812002"));
+        assert!(!validators.is_valid(Some("300"), "740812003"));
+        assert!(IdValidators::new().is_valid(Some("100"), "anything"));
+
+        let passport: ValidatedPassport =
+            "pid:799273988 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980 hcl:#623a2f cid:100"
+                .parse()
+                .unwrap();
+        assert_eq!(
+            validators.check(&passport),
+            Err(PassportError::BadChecksum(Field::Pid))
+        );
+    }
+}
@@ -0,0 +1,9 @@
+pub mod color;
+pub mod export;
+pub mod height;
+pub mod id_check;
+pub mod mrz;
+pub mod passport;
+pub mod query;
+pub mod repair;
+pub mod schema;
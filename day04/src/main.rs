@@ -1,6 +1,7 @@
-use std::{fs::read_to_string, str::FromStr};
+use std::{env, fs::read_to_string, str::FromStr};
 
-mod passport;
+use day04::{passport, repair, schema};
+use records::records;
 
 fn main() {
     let input = read_to_string("input.txt").expect("error reading input file");
@@ -11,19 +12,27 @@ fn main() {
         "{} super valid \"passports\" ;)",
         count_valid_passports_part2(&input)
     );
+
+    // `--repair` lists what to change in each record that doesn't validate
+    if env::args().any(|a| a == "--repair") {
+        for (line, suggestions) in repair::suggest_batch_repairs(&input, schema::Schema::puzzle()) {
+            println!("record at line {}:", line);
+            for suggestion in suggestions {
+                println!("  {}", suggestion);
+            }
+        }
+    }
 }
 
 fn count_valid_passports(passports: &str) -> usize {
-    passports
-        .split("\n\n")
-        .filter_map(|s| passport::Passport::from_str(s).ok())
+    records(passports)
+        .filter_map(|r| passport::Passport::from_str(&r.text).ok())
         .count()
 }
 
 fn count_valid_passports_part2(passports: &str) -> usize {
-    passports
-        .split("\n\n")
-        .filter_map(|s| passport::ValidatedPassport::from_str(s).ok())
+    records(passports)
+        .filter_map(|r| passport::ValidatedPassport::from_str(&r.text).ok())
         .count()
 }
 
@@ -78,4 +87,20 @@ eyr:2022
 iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719";
         assert_eq!(count_valid_passports_part2(input), 4);
     }
+
+    #[test]
+    fn crlf_and_messy_blank_lines() {
+        let input = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd\r
+byr:1937 iyr:2017 cid:147 hgt:183cm\r
+\r
+  \r
+\r
+hcl:#ae17e1 iyr:2013\r
+eyr:2024\r
+ecl:brn pid:760753108 byr:1931\r
+hgt:179cm\r
+";
+        assert_eq!(count_valid_passports(input), 2);
+        assert_eq!(count_valid_passports_part2(input), 2);
+    }
 }
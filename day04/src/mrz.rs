@@ -0,0 +1,430 @@
+use std::{convert::TryFrom, fmt, str::FromStr};
+
+use crate::{id_check::icao_check_digit, passport::ValidatedPassport};
+
+const LINE_LEN: usize = 44;
+
+// The two line machine readable zone printed on passports (ICAO 9303 TD3)
+// Only holds what a passport record and the MRZ have in common; height, hair and eye colour
+// have no place in it. Names and sex aren't in the records, so they're written as filler.
+// The full birth and issue years go in the personal number so no century has to be guessed,
+// behind a "YR" marker so a real personal number isn't mistaken for them.
+// Fields are only set through `Mrz::new`, so whatever it holds can be written out.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Mrz {
+    issuing_state: Option<String>,
+    passport_number: String,
+    birth_year: i32,
+    expiration_year: i32,
+    issue_year: Option<i32>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum MrzError {
+    // Not two lines of 44 characters
+    BadLength,
+    // Anything but A-Z, 0-9 and '<'
+    BadCharacter,
+    // Doesn't start with 'P'
+    NotAPassport,
+    BadCheckDigit(&'static str),
+    // A date or year that doesn't read as one
+    BadDate,
+    // A value that can't be written in its MRZ field
+    BadField(&'static str),
+}
+
+impl fmt::Display for MrzError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MrzError::BadLength => f.write_str("MRZ must be two lines of 44 characters"),
+            MrzError::BadCharacter => f.write_str("MRZ may only contain A-Z, 0-9 and <"),
+            MrzError::NotAPassport => f.write_str("MRZ is not for a passport"),
+            MrzError::BadCheckDigit(what) => write!(f, "bad {} check digit", what),
+            MrzError::BadDate => f.write_str("MRZ has a bad date"),
+            MrzError::BadField(what) => write!(f, "{} doesn't fit in an MRZ", what),
+        }
+    }
+}
+
+impl std::error::Error for MrzError {}
+
+fn pad(s: &str, len: usize) -> String {
+    format!("{:<<width$}", s, width = len)
+}
+
+const PERSONAL_MARKER: &str = "YR";
+
+fn check_digit(s: &str) -> char {
+    // `Mrz::new` only lets MRZ characters through
+    std::char::from_digit(icao_check_digit(s).unwrap(), 10).unwrap()
+}
+
+fn verify(field: &str, check: char, what: &'static str) -> Result<(), MrzError> {
+    let expected = icao_check_digit(field).ok_or(MrzError::BadCharacter)?;
+    // an empty optional field may leave its check digit as filler
+    if check == '<' && field.chars().all(|c| c == '<') {
+        return Ok(());
+    }
+    if check.to_digit(10) != Some(expected) {
+        return Err(MrzError::BadCheckDigit(what));
+    }
+    Ok(())
+}
+
+fn year(s: &str) -> Result<i32, MrzError> {
+    s.parse().map_err(|_| MrzError::BadDate)
+}
+
+fn is_mrz_text(s: &str) -> bool {
+    s.bytes()
+        .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+}
+
+// The birth and, if there is one, issue year `Mrz::lines` writes in the personal number
+// `None` for anything else, which is somebody's real personal number
+fn marked_years(personal: &str) -> Option<(&str, Option<&str>)> {
+    let years = personal.strip_prefix(PERSONAL_MARKER)?;
+    if !years.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    match years.len() {
+        4 => Some((years, None)),
+        8 => Some((&years[..4], Some(&years[4..]))),
+        _ => None,
+    }
+}
+
+impl Mrz {
+    // The state code is up to three of A-Z and 0-9, the passport number up to nine
+    // Years have to be written with four digits, and the expiry only has two so it has to
+    // be in 2000..=2099 to read back the same
+    pub fn new(
+        issuing_state: Option<String>,
+        passport_number: String,
+        birth_year: i32,
+        expiration_year: i32,
+        issue_year: Option<i32>,
+    ) -> Result<Mrz, MrzError> {
+        if issuing_state
+            .as_deref()
+            .is_some_and(|state| state.is_empty() || state.len() > 3 || !is_mrz_text(state))
+        {
+            return Err(MrzError::BadField("issuing state"));
+        }
+        if passport_number.len() > 9 || !is_mrz_text(&passport_number) {
+            return Err(MrzError::BadField("passport number"));
+        }
+        let four_digits = |year: i32| (1000..=9999).contains(&year);
+        if !four_digits(birth_year)
+            || !(2000..=2099).contains(&expiration_year)
+            || issue_year.is_some_and(|year| !four_digits(year))
+        {
+            return Err(MrzError::BadDate);
+        }
+        Ok(Mrz {
+            issuing_state,
+            passport_number,
+            birth_year,
+            expiration_year,
+            issue_year,
+        })
+    }
+
+    pub fn issuing_state(&self) -> Option<&str> {
+        self.issuing_state.as_deref()
+    }
+
+    pub fn passport_number(&self) -> &str {
+        &self.passport_number
+    }
+
+    pub fn birth_year(&self) -> i32 {
+        self.birth_year
+    }
+
+    pub fn expiration_year(&self) -> i32 {
+        self.expiration_year
+    }
+
+    pub fn issue_year(&self) -> Option<i32> {
+        self.issue_year
+    }
+
+    pub fn lines(&self) -> (String, String) {
+        let state = pad(self.issuing_state.as_deref().unwrap_or(""), 3);
+        let line1 = pad(&format!("P<{}", state), LINE_LEN);
+
+        let number = pad(&self.passport_number, 9);
+        // only the year is known, unknown month and day are filler
+        let birth = format!("{:02}<<<<", self.birth_year.rem_euclid(100));
+        let expiry = format!("{:02}<<<<", self.expiration_year.rem_euclid(100));
+        let issue = self.issue_year.map_or(String::new(), |y| y.to_string());
+        let personal = pad(
+            &format!("{}{}{}", PERSONAL_MARKER, self.birth_year, issue),
+            14,
+        );
+        let composite = format!(
+            "{}{}{}{}{}{}{}{}",
+            number,
+            check_digit(&number),
+            birth,
+            check_digit(&birth),
+            expiry,
+            check_digit(&expiry),
+            personal,
+            check_digit(&personal)
+        );
+        let line2 = format!(
+            "{}{}{}{}{}<{}{}{}{}",
+            number,
+            check_digit(&number),
+            state,
+            birth,
+            check_digit(&birth),
+            expiry,
+            check_digit(&expiry),
+            personal,
+            check_digit(&personal)
+        ) + &check_digit(&composite).to_string();
+        (line1, line2)
+    }
+
+    // The `key:value` fields the MRZ carries, add `hgt`, `hcl` and `ecl` to get a full record
+    pub fn to_record(&self) -> String {
+        let mut entries = vec![format!("byr:{}", self.birth_year)];
+        if let Some(issue_year) = self.issue_year {
+            entries.push(format!("iyr:{}", issue_year));
+        }
+        entries.push(format!("eyr:{}", self.expiration_year));
+        entries.push(format!("pid:{}", self.passport_number));
+        if let Some(state) = &self.issuing_state {
+            entries.push(format!("cid:{}", state));
+        }
+        entries.join(" ")
+    }
+}
+
+impl TryFrom<&ValidatedPassport> for Mrz {
+    type Error = MrzError;
+
+    // A `cid` longer than the three character state code is dropped, a shorter one is
+    // upper cased and has to fit `Mrz::new`
+    fn try_from(passport: &ValidatedPassport) -> Result<Mrz, MrzError> {
+        Mrz::new(
+            passport
+                .country_id
+                .as_ref()
+                .filter(|cid| cid.len() <= 3)
+                .map(|cid| cid.to_ascii_uppercase()),
+            passport.passport_id.clone(),
+            passport.birth_year,
+            passport.expiration_year,
+            Some(passport.issue_year),
+        )
+    }
+}
+
+impl fmt::Display for Mrz {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (line1, line2) = self.lines();
+        write!(f, "{}\n{}", line1, line2)
+    }
+}
+
+impl FromStr for Mrz {
+    type Err = MrzError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = s.lines().map(|l| l.trim_end()).collect();
+        let (line1, line2) = match lines.as_slice() {
+            [line1, line2] if line1.len() == LINE_LEN && line2.len() == LINE_LEN => (line1, line2),
+            _ => return Err(MrzError::BadLength),
+        };
+        if !line1
+            .chars()
+            .chain(line2.chars())
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '<')
+        {
+            return Err(MrzError::BadCharacter);
+        }
+        if !line1.starts_with('P') {
+            return Err(MrzError::NotAPassport);
+        }
+
+        let number = &line2[0..9];
+        let birth = &line2[13..19];
+        let expiry = &line2[21..27];
+        let personal = &line2[28..42];
+        let check = |i: usize| line2.as_bytes()[i] as char;
+        verify(number, check(9), "passport number")?;
+        verify(birth, check(19), "date of birth")?;
+        verify(expiry, check(27), "date of expiry")?;
+        verify(personal, check(42), "personal number")?;
+        let composite = format!("{}{}{}", &line2[0..10], &line2[13..20], &line2[21..43]);
+        verify(&composite, check(43), "composite")?;
+
+        let expiration_year = 2000 + year(&expiry[0..2])?;
+        let (birth_year, issue_year) = match marked_years(personal.trim_end_matches('<')) {
+            Some((birth_year, issue_year)) => (
+                year(birth_year)?,
+                match issue_year {
+                    Some(y) => Some(year(y)?),
+                    None => None,
+                },
+            ),
+            None => {
+                // no full year to go on, take the latest century that has them born before expiry
+                let yy = year(&birth[0..2])?;
+                if 2000 + yy <= expiration_year {
+                    (2000 + yy, None)
+                } else {
+                    (1900 + yy, None)
+                }
+            }
+        };
+        let state = line1[2..5].trim_end_matches('<');
+        Ok(Mrz {
+            issuing_state: if state.is_empty() {
+                None
+            } else {
+                Some(state.to_string())
+            },
+            passport_number: number.trim_end_matches('<').to_string(),
+            birth_year,
+            expiration_year,
+            issue_year,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let pstr = "eyr:2029 ecl:blu cid:129 byr:1989
+iyr:2014 pid:896056539 hcl:#a97842 hgt:165cm";
+        let passport: ValidatedPassport = pstr.parse().unwrap();
+        let mrz = Mrz::try_from(&passport).unwrap();
+        let (line1, line2) = mrz.lines();
+        assert_eq!(line1, "P<129<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<");
+        assert_eq!(line2, "896056539312989<<<<3<29<<<<1YR19892014<<<<90");
+        assert_eq!(line2.len(), 44);
+
+        let parsed: Mrz = mrz.to_string().parse().unwrap();
+        assert_eq!(parsed, mrz);
+        let record = format!("{} ecl:blu hcl:#a97842 hgt:165cm", parsed.to_record());
+        assert_eq!(record.parse::<ValidatedPassport>().unwrap(), passport);
+    }
+
+    #[test]
+    fn century_guess() {
+        let mrz = Mrz::new(None, "087499704".into(), 1980, 2030, None).unwrap();
+        let (line1, line2) = mrz.lines();
+        let without_personal = format!(
+            "{}\n{}",
+            line1,
+            // blank the personal number and redo the composite check digit
+            {
+                let mut line2 = line2[..28].to_string() + &"<".repeat(15);
+                let composite = format!("{}{}{}", &line2[0..10], &line2[13..20], &line2[21..43]);
+                line2.push(check_digit(&composite));
+                line2
+            }
+        );
+        let parsed: Mrz = without_personal.parse().unwrap();
+        assert_eq!(parsed.birth_year(), 1980);
+        assert_eq!(parsed.issue_year(), None);
+        assert_eq!(parsed.issuing_state(), None);
+    }
+
+    #[test]
+    fn bad_mrz() {
+        let mrz = Mrz::new(
+            Some("88".into()),
+            "545766238".into(),
+            2001,
+            2022,
+            Some(2015),
+        )
+        .unwrap();
+        let good = mrz.to_string();
+        assert_eq!(good.parse(), Ok(mrz));
+        assert_eq!("P<88".parse::<Mrz>(), Err(MrzError::BadLength));
+        assert_eq!(
+            good.replacen("P<", "V<", 1).parse::<Mrz>(),
+            Err(MrzError::NotAPassport)
+        );
+        assert_eq!(
+            good.replacen("P<", "p<", 1).parse::<Mrz>(),
+            Err(MrzError::BadCharacter)
+        );
+        assert_eq!(
+            good.replacen("545766238", "545766239", 1).parse::<Mrz>(),
+            Err(MrzError::BadCheckDigit("passport number"))
+        );
+    }
+
+    #[test]
+    fn icao_specimen() {
+        let specimen = "P<UTOERIKSSON<<ANNA<MARIA<<<<<<<<<<<<<<<<<<<
+L898902C36UTO7408122F1204159ZE184226B<<<<<10";
+        let mrz: Mrz = specimen.parse().unwrap();
+        assert_eq!(mrz.issuing_state(), Some("UTO"));
+        assert_eq!(mrz.passport_number(), "L898902C3");
+        assert_eq!(mrz.birth_year(), 1974);
+        assert_eq!(mrz.expiration_year(), 2012);
+        // ZE184226B is a personal number, not years
+        assert_eq!(mrz.issue_year(), None);
+
+        // issued by one state to a national of another
+        let foreign = specimen.replacen("P<UTO", "P<D<<", 1);
+        assert_eq!(foreign.parse::<Mrz>().unwrap().issuing_state(), Some("D"));
+    }
+
+    #[test]
+    fn bad_fields() {
+        assert_eq!(
+            Mrz::new(Some("1-2".into()), "1".into(), 1980, 2030, None),
+            Err(MrzError::BadField("issuing state"))
+        );
+        assert_eq!(
+            Mrz::new(None, "0123456789".into(), 1980, 2030, None),
+            Err(MrzError::BadField("passport number"))
+        );
+        assert_eq!(
+            Mrz::new(None, "l898902c3".into(), 1980, 2030, None),
+            Err(MrzError::BadField("passport number"))
+        );
+        assert_eq!(
+            Mrz::new(None, "1".into(), 19800, 2030, None),
+            Err(MrzError::BadDate)
+        );
+        // the expiry only has two digits, which read back as 20xx
+        assert_eq!(
+            Mrz::new(None, "1".into(), 1950, 1995, None),
+            Err(MrzError::BadDate)
+        );
+        assert_eq!(
+            Mrz::new(None, "1".into(), 1950, 2100, None),
+            Err(MrzError::BadDate)
+        );
+        for expiry in [2000, 2099] {
+            let mrz = Mrz::new(None, "1".into(), 1950, expiry, Some(1990)).unwrap();
+            assert_eq!(mrz.to_string().parse(), Ok(mrz));
+        }
+
+        let pstr = "eyr:2029 ecl:blu cid:ab byr:1989 iyr:2014 pid:896056539 hcl:#a97842 hgt:165cm";
+        let passport: ValidatedPassport = pstr.parse().unwrap();
+        let mrz = Mrz::try_from(&passport).unwrap();
+        assert_eq!(mrz.issuing_state(), Some("AB"));
+        assert_eq!(mrz.to_string().parse(), Ok(mrz));
+        let passport: ValidatedPassport = pstr.replace("cid:ab", "cid:1-2").parse().unwrap();
+        assert_eq!(
+            Mrz::try_from(&passport),
+            Err(MrzError::BadField("issuing state"))
+        );
+    }
+}
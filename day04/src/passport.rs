@@ -1,19 +1,32 @@
-use std::str::FromStr;
+use std::{collections::BTreeMap, fmt, str::FromStr};
 
+use serde::Deserialize;
+
+use crate::{
+    color::Color,
+    height::{Height, HeightError},
+    schema::Schema,
+};
+
+// Only checked for having the fields its schema requires, the values can be anything
+// A field is `None` when it was left out and the schema allows that, with the puzzle rules
+// only `cid` can be left out
 #[derive(Debug, Eq, PartialEq)]
 pub struct Passport {
-    pub birth_year: String,
-    pub issue_year: String,
-    pub expiration_year: String,
-    pub height: String,
-    pub hair_color: String,
-    pub eye_color: String,
-    pub passport_id: String,
+    pub birth_year: Option<String>,
+    pub issue_year: Option<String>,
+    pub expiration_year: Option<String>,
+    pub height: Option<String>,
+    pub hair_color: Option<String>,
+    pub eye_color: Option<String>,
+    pub passport_id: Option<String>,
     pub country_id: Option<String>,
+    // Keys that aren't passport fields, kept when parsing with `UnknownKeys::Collect`
+    pub extra: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Default)]
-struct PassportBuilder {
+pub(crate) struct PassportBuilder {
     birth_year: Option<String>,
     issue_year: Option<String>,
     expiration_year: Option<String>,
@@ -22,27 +35,26 @@ struct PassportBuilder {
     eye_color: Option<String>,
     passport_id: Option<String>,
     country_id: Option<String>,
+    extra: BTreeMap<String, String>,
 }
 
+// Passes the puzzle rules, so every field but `cid` is there and has its typed form
+// Passports checked against another schema stay a `Passport`, see `Passport::validate_with_schema`
 #[derive(Debug, Eq, PartialEq)]
 pub struct ValidatedPassport {
     pub birth_year: i32,
     pub issue_year: i32,
     pub expiration_year: i32,
     pub height: Height,
-    pub hair_color: String,
+    pub hair_color: Color,
     pub eye_color: EyeColor,
     pub passport_id: String,
     pub country_id: Option<String>,
+    // Keys that aren't passport fields, kept when parsing with `UnknownKeys::Collect`
+    pub extra: BTreeMap<String, String>,
 }
 
-#[derive(Debug, Eq, PartialEq)]
-pub enum Height {
-    Cm(i32),
-    Inch(i32),
-}
-
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
 pub enum EyeColor {
     Amb,
     Blu,
@@ -53,195 +65,623 @@ pub enum EyeColor {
     Oth,
 }
 
-//probably could have saved some headache in debugging by not cutting this corner
-//If I'm not going to do proper error handling, it is probably better to just `unwrap`
-//  rather than a unit error that throws away all context
-//Or use Box<Error>
-#[derive(Debug, Eq, PartialEq)]
-pub struct PassportError {}
+impl EyeColor {
+    pub const ALL: [EyeColor; 7] = [
+        EyeColor::Amb,
+        EyeColor::Blu,
+        EyeColor::Brn,
+        EyeColor::Gry,
+        EyeColor::Grn,
+        EyeColor::Hzl,
+        EyeColor::Oth,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            EyeColor::Amb => "amb",
+            EyeColor::Blu => "blu",
+            EyeColor::Brn => "brn",
+            EyeColor::Gry => "gry",
+            EyeColor::Grn => "grn",
+            EyeColor::Hzl => "hzl",
+            EyeColor::Oth => "oth",
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ParseEyeColorError;
+
+impl fmt::Display for ParseEyeColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("not one of amb blu brn gry grn hzl oth")
+    }
+}
+
+impl std::error::Error for ParseEyeColorError {}
+
+impl FromStr for EyeColor {
+    type Err = ParseEyeColorError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        EyeColor::ALL
+            .iter()
+            .find(|e| e.key() == s)
+            .cloned()
+            .ok_or(ParseEyeColorError)
+    }
+}
+
+impl fmt::Display for EyeColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Field {
+    Byr,
+    Iyr,
+    Eyr,
+    Hgt,
+    Hcl,
+    Ecl,
+    Pid,
+    Cid,
+}
+
+impl Field {
+    pub const ALL: [Field; 8] = [
+        Field::Byr,
+        Field::Iyr,
+        Field::Eyr,
+        Field::Hgt,
+        Field::Hcl,
+        Field::Ecl,
+        Field::Pid,
+        Field::Cid,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Field::Byr => "byr",
+            Field::Iyr => "iyr",
+            Field::Eyr => "eyr",
+            Field::Hgt => "hgt",
+            Field::Hcl => "hcl",
+            Field::Ecl => "ecl",
+            Field::Pid => "pid",
+            Field::Cid => "cid",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Field> {
+        Some(match key {
+            "byr" => Field::Byr,
+            "iyr" => Field::Iyr,
+            "eyr" => Field::Eyr,
+            "hgt" => Field::Hgt,
+            "hcl" => Field::Hcl,
+            "ecl" => Field::Ecl,
+            "pid" => Field::Pid,
+            "cid" => Field::Cid,
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+// What to do when a record gives the same key twice
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum DuplicateKeys {
+    Reject,
+    LastWins,
+    FirstWins,
+}
+
+// What to do with keys that aren't passport fields
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum UnknownKeys {
+    Reject,
+    Ignore,
+    // Keep them in the passport's `extra` map
+    Collect,
+}
+
+// How strict parsing a record is, by default a repeated key keeps its last value as it
+// always has and unknown keys are rejected
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ParseOptions {
+    pub duplicate_keys: DuplicateKeys,
+    pub unknown_keys: UnknownKeys,
+}
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions {
+            duplicate_keys: DuplicateKeys::LastWins,
+            unknown_keys: UnknownKeys::Reject,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+pub enum PassportError {
+    Missing(Field),
+    NotANumber(Field),
+    OutOfRange(Field),
+    BadUnit(Field),
+    BadFormat(Field),
+    // Failed the country's `IdValidator`
+    BadChecksum(Field),
+    UnknownKey(String),
+    DuplicateKey(Field),
+    DuplicateUnknownKey(String),
+    // An unknown key kept with `UnknownKeys::Collect` that has no ':' after it
+    MissingValue(String),
+}
+
+impl PassportError {
+    // `None` for keys that aren't passport fields at all
+    pub fn field(&self) -> Option<Field> {
+        match self {
+            PassportError::Missing(field)
+            | PassportError::NotANumber(field)
+            | PassportError::OutOfRange(field)
+            | PassportError::BadUnit(field)
+            | PassportError::BadFormat(field)
+            | PassportError::BadChecksum(field)
+            | PassportError::DuplicateKey(field) => Some(*field),
+            PassportError::UnknownKey(_)
+            | PassportError::DuplicateUnknownKey(_)
+            | PassportError::MissingValue(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for PassportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PassportError::Missing(field) => write!(f, "{} is missing", field),
+            PassportError::NotANumber(field) => write!(f, "{} is not a number", field),
+            PassportError::OutOfRange(field) => write!(f, "{} is out of range", field),
+            PassportError::BadUnit(field) => write!(f, "{} has a bad unit", field),
+            PassportError::BadFormat(field) => write!(f, "{} is badly formatted", field),
+            PassportError::BadChecksum(field) => write!(f, "{} has a bad check digit", field),
+            PassportError::UnknownKey(key) => write!(f, "unknown key {:?}", key),
+            PassportError::DuplicateKey(field) => write!(f, "{} is given more than once", field),
+            PassportError::DuplicateUnknownKey(key) => {
+                write!(f, "unknown key {:?} is given more than once", key)
+            }
+            PassportError::MissingValue(key) => write!(f, "unknown key {:?} has no value", key),
+        }
+    }
+}
+
+impl std::error::Error for PassportError {}
+
+// Every problem found in a record, parsing carries on past the first one
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct PassportErrors(pub Vec<PassportError>);
+
+impl fmt::Display for PassportErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, e) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{}", e)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for PassportErrors {}
 
 type Result<T> = std::result::Result<T, PassportError>;
 
+// Keeps the first error of each field, the value is `None` if there was one
+fn collect<T>(result: Result<T>, errors: &mut Vec<PassportError>) -> Option<T> {
+    result.map_err(|e| errors.push(e)).ok()
+}
+
 impl PassportBuilder {
-    fn build(self) -> Result<Passport> {
+    pub(crate) fn value(&self, field: Field) -> Option<&str> {
+        match field {
+            Field::Byr => self.birth_year.as_deref(),
+            Field::Iyr => self.issue_year.as_deref(),
+            Field::Eyr => self.expiration_year.as_deref(),
+            Field::Hgt => self.height.as_deref(),
+            Field::Hcl => self.hair_color.as_deref(),
+            Field::Ecl => self.eye_color.as_deref(),
+            Field::Pid => self.passport_id.as_deref(),
+            Field::Cid => self.country_id.as_deref(),
+        }
+    }
+
+    fn check(&self, schema: &Schema) -> std::result::Result<(), PassportErrors> {
+        let errors: Vec<_> = Field::ALL
+            .iter()
+            .filter_map(|field| schema.check(*field, self.value(*field)).err())
+            .collect();
+        if !errors.is_empty() {
+            return Err(PassportErrors(errors));
+        }
+        Ok(())
+    }
+
+    // Only checks that the fields `schema` requires are there, not their values
+    fn build(self, schema: &Schema) -> std::result::Result<Passport, PassportErrors> {
+        let errors: Vec<_> = Field::ALL
+            .iter()
+            .filter(|field| {
+                self.value(**field).is_none() && schema.field(**field).is_some_and(|f| f.required)
+            })
+            .map(|field| PassportError::Missing(*field))
+            .collect();
+        if !errors.is_empty() {
+            return Err(PassportErrors(errors));
+        }
         Ok(Passport {
-            birth_year: self.birth_year.ok_or(PassportError {})?,
-            issue_year: self.issue_year.ok_or(PassportError {})?,
-            expiration_year: self.expiration_year.ok_or(PassportError {})?,
-            height: self.height.ok_or(PassportError {})?,
-            hair_color: self.hair_color.ok_or(PassportError {})?,
-            eye_color: self.eye_color.ok_or(PassportError {})?,
-            passport_id: self.passport_id.ok_or(PassportError {})?,
+            birth_year: self.birth_year,
+            issue_year: self.issue_year,
+            expiration_year: self.expiration_year,
+            height: self.height,
+            hair_color: self.hair_color,
+            eye_color: self.eye_color,
+            passport_id: self.passport_id,
             country_id: self.country_id,
+            extra: self.extra,
         })
     }
 
-    fn build_validated(self) -> Result<ValidatedPassport> {
+    fn build_validated(self) -> std::result::Result<ValidatedPassport, PassportErrors> {
+        let schema = Schema::puzzle();
+        let mut errors = Vec::new();
+        let birth_year = collect(validate_byr(schema, self.birth_year), &mut errors);
+        let issue_year = collect(validate_iyr(schema, self.issue_year), &mut errors);
+        let expiration_year = collect(validate_eyr(schema, self.expiration_year), &mut errors);
+        let height = collect(validate_hgt(schema, self.height), &mut errors);
+        let hair_color = collect(validate_hcl(schema, self.hair_color), &mut errors);
+        let eye_color = collect(validate_ecl(schema, self.eye_color), &mut errors);
+        let passport_id = collect(validate_pid(schema, self.passport_id), &mut errors);
+        collect(
+            schema.check(Field::Cid, self.country_id.as_deref()),
+            &mut errors,
+        );
+        if !errors.is_empty() {
+            return Err(PassportErrors(errors));
+        }
         Ok(ValidatedPassport {
-            birth_year: validate_byr(self.birth_year)?,
-            issue_year: validate_iyr(self.issue_year)?,
-            expiration_year: validate_eyr(self.expiration_year)?,
-            height: validate_hgt(self.height)?,
-            hair_color: validate_hcl(self.hair_color)?,
-            eye_color: validate_ecl(self.eye_color)?,
-            passport_id: validate_pid(self.passport_id)?,
+            birth_year: birth_year.unwrap(),
+            issue_year: issue_year.unwrap(),
+            expiration_year: expiration_year.unwrap(),
+            height: height.unwrap(),
+            hair_color: hair_color.unwrap(),
+            eye_color: eye_color.unwrap(),
+            passport_id: passport_id.unwrap(),
             country_id: self.country_id,
+            extra: self.extra,
         })
     }
 }
 
-fn validate_byr(birth_year: Option<String>) -> Result<i32> {
-    let i: i32 = birth_year
-        .and_then(|s| s.parse().ok())
-        .ok_or(PassportError {})?;
-    if i < 1920 || i > 2002 {
-        return Err(PassportError {});
-    }
-    Ok(i)
+// Checks any field's value against `schema`
+pub(crate) fn validate_field(schema: &Schema, field: Field, value: Option<&str>) -> Result<()> {
+    schema.check(field, value)
 }
-fn validate_iyr(issue_year: Option<String>) -> Result<i32> {
-    let i: i32 = issue_year
-        .and_then(|s| s.parse().ok())
-        .ok_or(PassportError {})?;
-    if i < 2010 || i > 2020 {
-        return Err(PassportError {});
-    }
-    Ok(i)
+
+// The validators below check a value against `schema`, then turn it into its typed form
+// The typed form needs a value, so a left out field is missing whatever the schema says
+fn validate<T>(
+    schema: &Schema,
+    field: Field,
+    value: Option<String>,
+    typed: impl FnOnce(String) -> Result<T>,
+) -> Result<T> {
+    schema.check(field, value.as_deref())?;
+    typed(value.ok_or(PassportError::Missing(field))?)
+}
+fn validate_year(schema: &Schema, year: Option<String>, field: Field) -> Result<i32> {
+    validate(schema, field, year, |year| {
+        year.parse().map_err(|_| PassportError::NotANumber(field))
+    })
+}
+fn validate_byr(schema: &Schema, birth_year: Option<String>) -> Result<i32> {
+    validate_year(schema, birth_year, Field::Byr)
+}
+fn validate_iyr(schema: &Schema, issue_year: Option<String>) -> Result<i32> {
+    validate_year(schema, issue_year, Field::Iyr)
+}
+fn validate_eyr(schema: &Schema, expiration_year: Option<String>) -> Result<i32> {
+    validate_year(schema, expiration_year, Field::Eyr)
+}
+fn validate_hgt(schema: &Schema, height: Option<String>) -> Result<Height> {
+    validate(schema, Field::Hgt, height, |height| {
+        height.parse().map_err(|e| match e {
+            HeightError::NotANumber => PassportError::NotANumber(Field::Hgt),
+            HeightError::BadUnit => PassportError::BadUnit(Field::Hgt),
+            HeightError::OutOfRange => PassportError::OutOfRange(Field::Hgt),
+        })
+    })
+}
+fn validate_hcl(schema: &Schema, hair_color: Option<String>) -> Result<Color> {
+    validate(schema, Field::Hcl, hair_color, |hair_color| {
+        hair_color
+            .parse()
+            .map_err(|_| PassportError::BadFormat(Field::Hcl))
+    })
+}
+fn validate_ecl(schema: &Schema, eye_color: Option<String>) -> Result<EyeColor> {
+    validate(schema, Field::Ecl, eye_color, |eye_color| {
+        eye_color
+            .parse()
+            .map_err(|_| PassportError::BadFormat(Field::Ecl))
+    })
+}
+fn validate_pid(schema: &Schema, passport_id: Option<String>) -> Result<String> {
+    validate(schema, Field::Pid, passport_id, Ok)
 }
-fn validate_eyr(expiration_year: Option<String>) -> Result<i32> {
-    let i: i32 = expiration_year
-        .and_then(|s| s.parse().ok())
-        .ok_or(PassportError {})?;
-    if i < 2020 || i > 2030 {
-        return Err(PassportError {});
+
+// Adds problems with the values after problems with the keys
+// A field that was already badly given isn't reported again as missing or invalid
+pub(crate) fn merge_errors(errors: &mut Vec<PassportError>, invalid: Vec<PassportError>) {
+    let reported: Vec<_> = errors.iter().filter_map(|e| e.field()).collect();
+    errors.extend(
+        invalid
+            .into_iter()
+            .filter(|e| e.field().is_none_or(|f| !reported.contains(&f))),
+    );
+}
+
+// Stores `value` in `slot` following `policy`, `false` if it's a rejected duplicate
+fn store(slot: &mut Option<String>, value: &str, policy: DuplicateKeys) -> bool {
+    match (slot.is_some(), policy) {
+        (false, _) | (true, DuplicateKeys::LastWins) => *slot = Some(value.into()),
+        (true, DuplicateKeys::FirstWins) => (),
+        (true, DuplicateKeys::Reject) => return false,
     }
-    Ok(i)
+    true
 }
-fn validate_hgt(height: Option<String>) -> Result<Height> {
-    //not really happy with this
-    let height = height.ok_or(PassportError {})?;
-    let unit = height
-        .as_bytes()
-        .iter()
-        .rev()
-        .take(2)
-        .rev()
-        .cloned()
-        .collect::<Vec<_>>();
-    let value = height
-        .as_bytes()
-        .iter()
-        .rev()
-        .skip(2)
-        .rev()
-        .cloned()
-        .collect::<Vec<_>>();
-    let value = std::str::from_utf8(value.as_slice())
-        .map_err(|_| PassportError {})?
-        .parse::<i32>()
-        .map_err(|_| PassportError {})?;
-    Ok(match unit.as_slice() {
-        b"cm" => {
-            if value < 150 || value > 193 {
-                return Err(PassportError {});
-            } else {
-                Height::Cm(value)
+
+impl PassportBuilder {
+    // Whatever could be read from the record, along with every problem reading it
+    pub(crate) fn read(s: &str, options: &ParseOptions) -> (Self, Vec<PassportError>) {
+        let mut builder = PassportBuilder::default();
+        let mut errors = Vec::new();
+        for entry in s.split_whitespace() {
+            let (key, value) = match entry.split_once(':') {
+                Some((key, value)) => (key, Some(value)),
+                None => (entry, None),
+            };
+            let field = match Field::from_key(key) {
+                Some(field) => field,
+                None => {
+                    match options.unknown_keys {
+                        UnknownKeys::Reject => errors.push(PassportError::UnknownKey(key.into())),
+                        UnknownKeys::Ignore => (),
+                        UnknownKeys::Collect => {
+                            // there's no way to write a key without its ':' back out
+                            let value = match value {
+                                Some(value) => value,
+                                None => {
+                                    errors.push(PassportError::MissingValue(key.into()));
+                                    continue;
+                                }
+                            };
+                            let mut slot = builder.extra.get(key).cloned();
+                            if store(&mut slot, value, options.duplicate_keys) {
+                                builder.extra.insert(key.into(), slot.unwrap());
+                            } else {
+                                errors.push(PassportError::DuplicateUnknownKey(key.into()));
+                            }
+                        }
+                    }
+                    continue;
+                }
+            };
+            let value = match value {
+                Some(value) => value,
+                None => {
+                    errors.push(PassportError::BadFormat(field));
+                    continue;
+                }
+            };
+            let slot = match field {
+                Field::Byr => &mut builder.birth_year,
+                Field::Iyr => &mut builder.issue_year,
+                Field::Eyr => &mut builder.expiration_year,
+                Field::Hgt => &mut builder.height,
+                Field::Hcl => &mut builder.hair_color,
+                Field::Ecl => &mut builder.eye_color,
+                Field::Pid => &mut builder.passport_id,
+                Field::Cid => &mut builder.country_id,
+            };
+            if !store(slot, value, options.duplicate_keys) {
+                errors.push(PassportError::DuplicateKey(field));
             }
         }
-        b"in" => {
-            if value < 59 || value > 76 {
-                return Err(PassportError {});
-            } else {
-                Height::Inch(value)
+        (builder, errors)
+    }
+
+    pub(crate) fn parse(
+        s: &str,
+        options: &ParseOptions,
+    ) -> std::result::Result<Self, PassportErrors> {
+        let (builder, errors) = PassportBuilder::read(s, options);
+        if !errors.is_empty() {
+            return Err(PassportErrors(errors));
+        }
+        Ok(builder)
+    }
+
+    // Reports problems with the keys and with the values together
+    fn parse_validated(
+        s: &str,
+        options: &ParseOptions,
+    ) -> std::result::Result<ValidatedPassport, PassportErrors> {
+        let (builder, mut errors) = PassportBuilder::read(s, options);
+        match builder.build_validated() {
+            Ok(passport) if errors.is_empty() => Ok(passport),
+            Ok(_) => Err(PassportErrors(errors)),
+            Err(PassportErrors(invalid)) => {
+                merge_errors(&mut errors, invalid);
+                Err(PassportErrors(errors))
             }
         }
-        _ => return Err(PassportError {}),
-    })
+    }
 }
-fn validate_hcl(hair_color: Option<String>) -> Result<String> {
-    let hair_color = hair_color.ok_or(PassportError {})?;
-    if hair_color.len() != 7 {
-        return Err(PassportError {});
+
+impl FromStr for PassportBuilder {
+    type Err = PassportErrors;
+
+    fn from_str(s: &str) -> std::result::Result<Self, PassportErrors> {
+        PassportBuilder::parse(s, &ParseOptions::default())
     }
-    let iter = hair_color.as_bytes().iter().cloned().enumerate();
-    for (i, c) in iter {
-        if i == 0 {
-            if c != b'#' {
-                return Err(PassportError {});
-            }
-            continue;
+}
+
+impl FromStr for Passport {
+    type Err = PassportErrors;
+
+    fn from_str(s: &str) -> std::result::Result<Self, PassportErrors> {
+        PassportBuilder::from_str(s)?.build(Schema::puzzle())
+    }
+}
+
+impl Passport {
+    pub fn parse_with(
+        s: &str,
+        options: &ParseOptions,
+    ) -> std::result::Result<Passport, PassportErrors> {
+        PassportBuilder::parse(s, options)?.build(Schema::puzzle())
+    }
+
+    // Like `from_str`, but the fields a country's `schema` requires have to be there instead
+    // of the puzzle's
+    pub fn from_str_with_schema(
+        s: &str,
+        schema: &Schema,
+    ) -> std::result::Result<Passport, PassportErrors> {
+        PassportBuilder::from_str(s)?.build(schema)
+    }
+
+    // Also checks every value against `schema`, reporting problems with the keys and the
+    // values together
+    pub fn validate_with_schema(
+        s: &str,
+        schema: &Schema,
+    ) -> std::result::Result<Passport, PassportErrors> {
+        let (builder, mut errors) = PassportBuilder::read(s, &ParseOptions::default());
+        if let Err(PassportErrors(invalid)) = builder.check(schema) {
+            merge_errors(&mut errors, invalid);
         }
-        match c {
-            b'0'..=b'9' => continue,
-            b'a'..=b'f' => continue,
-            _ => return Err(PassportError {}),
+        if !errors.is_empty() {
+            return Err(PassportErrors(errors));
         }
+        builder.build(schema)
     }
-    Ok(hair_color)
-}
-fn validate_ecl(eye_color: Option<String>) -> Result<EyeColor> {
-    Ok(match eye_color.ok_or(PassportError {})?.as_str() {
-        "amb" => EyeColor::Amb,
-        "blu" => EyeColor::Blu,
-        "brn" => EyeColor::Brn,
-        "gry" => EyeColor::Gry,
-        "grn" => EyeColor::Grn,
-        "hzl" => EyeColor::Hzl,
-        "oth" => EyeColor::Oth,
-        _ => return Err(PassportError {}),
-    })
 }
-fn validate_pid(passport_id: Option<String>) -> Result<String> {
-    let passport_id = passport_id.ok_or(PassportError {})?;
-    if passport_id.len() != 9 {
-        return Err(PassportError {});
+
+// A passport as the `key:value` entries of the batch format, in `Field::ALL` order
+// Fields the passport doesn't have are left out
+pub trait Record: fmt::Display {
+    fn entries(&self) -> Vec<(Field, String)>;
+    fn extra(&self) -> &BTreeMap<String, String>;
+}
+
+impl Record for Passport {
+    fn entries(&self) -> Vec<(Field, String)> {
+        vec![
+            (Field::Byr, self.birth_year.clone()),
+            (Field::Iyr, self.issue_year.clone()),
+            (Field::Eyr, self.expiration_year.clone()),
+            (Field::Hgt, self.height.clone()),
+            (Field::Hcl, self.hair_color.clone()),
+            (Field::Ecl, self.eye_color.clone()),
+            (Field::Pid, self.passport_id.clone()),
+            (Field::Cid, self.country_id.clone()),
+        ]
+        .into_iter()
+        .filter_map(|(field, value)| Some((field, value?)))
+        .collect()
     }
-    let iter = passport_id.as_bytes().iter().cloned();
-    for c in iter {
-        match c {
-            b'0'..=b'9' => continue,
-            _ => return Err(PassportError {}),
-        }
+
+    fn extra(&self) -> &BTreeMap<String, String> {
+        &self.extra
     }
-    Ok(passport_id)
 }
 
-impl FromStr for PassportBuilder {
-    type Err = PassportError;
+impl Record for ValidatedPassport {
+    fn entries(&self) -> Vec<(Field, String)> {
+        let mut entries = vec![
+            (Field::Byr, self.birth_year.to_string()),
+            (Field::Iyr, self.issue_year.to_string()),
+            (Field::Eyr, self.expiration_year.to_string()),
+            (Field::Hgt, self.height.to_string()),
+            (Field::Hcl, self.hair_color.to_string()),
+            (Field::Ecl, self.eye_color.to_string()),
+            (Field::Pid, self.passport_id.clone()),
+        ];
+        if let Some(cid) = &self.country_id {
+            entries.push((Field::Cid, cid.clone()));
+        }
+        entries
+    }
 
-    fn from_str(s: &str) -> Result<Self> {
-        let mut builder = PassportBuilder::default();
-        for entry in s.split_whitespace() {
-            let parts: Vec<_> = entry.split(':').collect();
-            match parts[0] {
-                "byr" => builder.birth_year = Some(parts[1].into()),
-                "iyr" => builder.issue_year = Some(parts[1].into()),
-                "eyr" => builder.expiration_year = Some(parts[1].into()),
-                "hgt" => builder.height = Some(parts[1].into()),
-                "hcl" => builder.hair_color = Some(parts[1].into()),
-                "ecl" => builder.eye_color = Some(parts[1].into()),
-                "pid" => builder.passport_id = Some(parts[1].into()),
-                "cid" => builder.country_id = Some(parts[1].into()),
-                _ => return Err(PassportError {}),
-            }
+    fn extra(&self) -> &BTreeMap<String, String> {
+        &self.extra
+    }
+}
+
+// Extra keys go last, in key order
+fn fmt_record<T: Record>(record: &T, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let entries = record.entries();
+    let fields = entries
+        .iter()
+        .map(|(field, value)| (field.key(), value.as_str()));
+    let extra = record.extra().iter().map(|(k, v)| (k.as_str(), v.as_str()));
+    for (i, (key, value)) in fields.chain(extra).enumerate() {
+        if i > 0 {
+            f.write_str(" ")?;
         }
-        Ok(builder)
+        write!(f, "{}:{}", key, value)?;
     }
+    Ok(())
 }
 
-impl FromStr for Passport {
-    type Err = PassportError;
+// One line of the batch format, parsing it back gives the same passport
+impl fmt::Display for Passport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_record(self, f)
+    }
+}
 
-    fn from_str(s: &str) -> Result<Self> {
-        PassportBuilder::from_str(s)?.build()
+impl fmt::Display for ValidatedPassport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_record(self, f)
+    }
+}
+
+impl ValidatedPassport {
+    pub fn parse_with(
+        s: &str,
+        options: &ParseOptions,
+    ) -> std::result::Result<ValidatedPassport, PassportErrors> {
+        PassportBuilder::parse_validated(s, options)
     }
 }
 
 impl FromStr for ValidatedPassport {
-    type Err = PassportError;
+    type Err = PassportErrors;
 
-    fn from_str(s: &str) -> Result<Self> {
-        PassportBuilder::from_str(s)?.build_validated()
+    fn from_str(s: &str) -> std::result::Result<Self, PassportErrors> {
+        PassportBuilder::parse_validated(s, &ParseOptions::default())
     }
 }
 
@@ -255,21 +695,25 @@ mod tests {
 byr:1937 iyr:2017 cid:147 hgt:183cm";
         assert_eq!(
             Passport {
-                birth_year: "1937".into(),
-                issue_year: "2017".into(),
-                expiration_year: "2020".into(),
-                height: "183cm".into(),
-                hair_color: "#fffffd".into(),
-                eye_color: "gry".into(),
-                passport_id: "860033327".into(),
-                country_id: Some("147".into())
+                birth_year: Some("1937".into()),
+                issue_year: Some("2017".into()),
+                expiration_year: Some("2020".into()),
+                height: Some("183cm".into()),
+                hair_color: Some("#fffffd".into()),
+                eye_color: Some("gry".into()),
+                passport_id: Some("860033327".into()),
+                country_id: Some("147".into()),
+                extra: BTreeMap::new()
             },
             Passport::from_str(pstr1).unwrap()
         );
 
         let pstr2 = "iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
 hcl:#cfa07d byr:1929";
-        assert_eq!(PassportError {}, Passport::from_str(pstr2).unwrap_err());
+        assert_eq!(
+            PassportErrors(vec![PassportError::Missing(Field::Hgt)]),
+            Passport::from_str(pstr2).unwrap_err()
+        );
 
         let pstr3 = "hcl:#ae17e1 iyr:2013
 eyr:2024
@@ -277,97 +721,343 @@ ecl:brn pid:760753108 byr:1931
 hgt:179cm";
         assert_eq!(
             Passport {
-                birth_year: "1931".into(),
-                issue_year: "2013".into(),
-                expiration_year: "2024".into(),
-                height: "179cm".into(),
-                hair_color: "#ae17e1".into(),
-                eye_color: "brn".into(),
-                passport_id: "760753108".into(),
-                country_id: None
+                birth_year: Some("1931".into()),
+                issue_year: Some("2013".into()),
+                expiration_year: Some("2024".into()),
+                height: Some("179cm".into()),
+                hair_color: Some("#ae17e1".into()),
+                eye_color: Some("brn".into()),
+                passport_id: Some("760753108".into()),
+                country_id: None,
+                extra: BTreeMap::new()
             },
             Passport::from_str(pstr3).unwrap()
         );
 
         let pstr4 = "hcl:#cfa07d eyr:2025 pid:166559648
 iyr:2011 ecl:brn hgt:59in";
-        assert_eq!(PassportError {}, Passport::from_str(pstr4).unwrap_err());
+        assert_eq!(
+            PassportErrors(vec![PassportError::Missing(Field::Byr)]),
+            Passport::from_str(pstr4).unwrap_err()
+        );
+    }
+    #[test]
+    fn passport_bad_keys() {
+        let pstr = "byr:1937 byr:1938 foo:bar iyr eyr:2020";
+        assert_eq!(
+            PassportErrors(vec![
+                PassportError::UnknownKey("foo".into()),
+                PassportError::BadFormat(Field::Iyr)
+            ]),
+            Passport::from_str(pstr).unwrap_err()
+        );
+        let options = ParseOptions {
+            duplicate_keys: DuplicateKeys::Reject,
+            ..ParseOptions::default()
+        };
+        assert_eq!(
+            PassportErrors(vec![
+                PassportError::DuplicateKey(Field::Byr),
+                PassportError::UnknownKey("foo".into()),
+                PassportError::BadFormat(Field::Iyr)
+            ]),
+            Passport::parse_with(pstr, &options).unwrap_err()
+        );
+
+        // a repeated key keeps its last value by default
+        let pstr = "byr:1937 iyr:2017 eyr:2020 hgt:183cm hcl:#fffffd ecl:gry pid:860033327
+byr:1938";
+        assert_eq!(
+            Passport::from_str(pstr).unwrap().birth_year.as_deref(),
+            Some("1938")
+        );
+    }
+    #[test]
+    fn passport_with_schema() {
+        let schema = Schema::from_toml(
+            r#"
+            [fields.hgt]
+            type = "measure"
+            units = { mm = [1400, 2100] }
+
+            [fields.cid]
+            type = "number"
+            "#,
+        )
+        .unwrap();
+        let pstr = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:1830mm";
+        assert_eq!(
+            Some("1830mm".into()),
+            Passport::from_str_with_schema(pstr, &schema)
+                .unwrap()
+                .height
+        );
+        let pstr = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 hgt:183cm";
+        assert_eq!(
+            PassportErrors(vec![PassportError::Missing(Field::Cid)]),
+            Passport::from_str_with_schema(pstr, &schema).unwrap_err()
+        );
+        assert_eq!(
+            PassportErrors(vec![
+                PassportError::BadUnit(Field::Hgt),
+                PassportError::Missing(Field::Cid)
+            ]),
+            Passport::validate_with_schema(pstr, &schema).unwrap_err()
+        );
+        // values aren't checked without `validate_with_schema`, with any schema
+        let pstr = "ecl:zzz pid:1 eyr:1 hcl:1 byr:1 iyr:1 hgt:1";
+        assert_eq!(
+            Passport::from_str(pstr),
+            Passport::from_str_with_schema(pstr, Schema::puzzle())
+        );
+        assert!(Passport::from_str(pstr).is_ok());
+        let schema = Schema::from_toml(
+            r#"
+            [fields.hgt]
+            type = "height"
+            required = false
+
+            [fields.byr]
+            type = "number"
+            min = 1900
+            "#,
+        )
+        .unwrap();
+        let pstr = "ecl:gry pid:860033327 byr:1937";
+        let passport = Passport::from_str_with_schema(pstr, &schema).unwrap();
+        assert_eq!(passport.height, None);
+        assert_eq!(passport.issue_year, None);
+        assert_eq!(passport.to_string(), "byr:1937 ecl:gry pid:860033327");
+        assert_eq!(
+            Passport::validate_with_schema(pstr, &schema).unwrap(),
+            passport
+        );
+        assert_eq!(
+            PassportErrors(vec![
+                PassportError::UnknownKey("foo".into()),
+                PassportError::OutOfRange(Field::Byr)
+            ]),
+            Passport::validate_with_schema("byr:1850 hgt:2m foo:bar", &schema).unwrap_err()
+        );
+        assert_eq!(
+            PassportErrors(vec![PassportError::Missing(Field::Hgt)]),
+            ValidatedPassport::from_str(
+                "ecl:gry pid:860033327 byr:1937 iyr:2017 eyr:2020 hcl:#fffffd"
+            )
+            .unwrap_err()
+        );
+    }
+    #[test]
+    fn passport_round_trip() {
+        let pstr = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183cm";
+        let passport = Passport::from_str(pstr).unwrap();
+        assert_eq!(
+            passport.to_string(),
+            "byr:1937 iyr:2017 eyr:2020 hgt:183cm hcl:#fffffd ecl:gry pid:860033327 cid:147"
+        );
+        assert_eq!(Passport::from_str(&passport.to_string()).unwrap(), passport);
+
+        let pstr = "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980
+hcl:#623a2f";
+        let passport = ValidatedPassport::from_str(pstr).unwrap();
+        assert_eq!(
+            passport.to_string(),
+            "byr:1980 iyr:2012 eyr:2030 hgt:74in hcl:#623a2f ecl:grn pid:087499704"
+        );
+        assert_eq!(
+            ValidatedPassport::from_str(&passport.to_string()).unwrap(),
+            passport
+        );
+    }
+    #[test]
+    fn passport_key_policies() {
+        let pstr = "byr:1937 iyr:2017 eyr:2020 hgt:183cm hcl:#fffffd ecl:gry pid:860033327
+byr:1938 foo:bar foo:baz";
+        let options = ParseOptions {
+            duplicate_keys: DuplicateKeys::LastWins,
+            unknown_keys: UnknownKeys::Collect,
+        };
+        let passport = Passport::parse_with(pstr, &options).unwrap();
+        assert_eq!(passport.birth_year.as_deref(), Some("1938"));
+        assert_eq!(passport.extra.get("foo").unwrap(), "baz");
+        assert_eq!(
+            passport.to_string(),
+            "byr:1938 iyr:2017 eyr:2020 hgt:183cm hcl:#fffffd ecl:gry pid:860033327 foo:baz"
+        );
+        assert_eq!(
+            Passport::parse_with(&passport.to_string(), &options).unwrap(),
+            passport
+        );
+
+        let options = ParseOptions {
+            duplicate_keys: DuplicateKeys::FirstWins,
+            unknown_keys: UnknownKeys::Ignore,
+        };
+        let passport = ValidatedPassport::parse_with(pstr, &options).unwrap();
+        assert_eq!(passport.birth_year, 1937);
+        assert!(passport.extra.is_empty());
+
+        let options = ParseOptions {
+            duplicate_keys: DuplicateKeys::Reject,
+            unknown_keys: UnknownKeys::Collect,
+        };
+        assert_eq!(
+            PassportErrors(vec![
+                PassportError::DuplicateKey(Field::Byr),
+                PassportError::DuplicateUnknownKey("foo".into())
+            ]),
+            Passport::parse_with(pstr, &options).unwrap_err()
+        );
+
+        let options = ParseOptions {
+            duplicate_keys: DuplicateKeys::Reject,
+            unknown_keys: UnknownKeys::Collect,
+        };
+        let pstr = "byr:1937 iyr:2017 eyr:2020 hgt:183cm hcl:#fffffd ecl:gry pid:860033327 foo";
+        assert_eq!(
+            PassportErrors(vec![PassportError::MissingValue("foo".into())]),
+            Passport::parse_with(pstr, &options).unwrap_err()
+        );
+        let passport = Passport::parse_with(&pstr.replace("foo", "foo:"), &options).unwrap();
+        assert_eq!(passport.extra.get("foo").unwrap(), "");
+        assert_eq!(
+            Passport::parse_with(&passport.to_string(), &options).unwrap(),
+            passport
+        );
     }
     #[test]
     fn validated_byr() {
-        assert_eq!(validate_byr(Some("2002".into())).unwrap(), 2002);
         assert_eq!(
-            validate_byr(Some("2003".into())).unwrap_err(),
-            PassportError {}
+            validate_byr(Schema::puzzle(), Some("2002".into())).unwrap(),
+            2002
+        );
+        assert_eq!(
+            validate_byr(Schema::puzzle(), Some("19x0".into())).unwrap_err(),
+            PassportError::NotANumber(Field::Byr)
+        );
+        assert_eq!(
+            validate_byr(Schema::puzzle(), None).unwrap_err(),
+            PassportError::Missing(Field::Byr)
+        );
+        assert_eq!(
+            validate_byr(Schema::puzzle(), Some("2003".into())).unwrap_err(),
+            PassportError::OutOfRange(Field::Byr)
         );
     }
     #[test]
     fn validated_iyr() {
-        assert_eq!(validate_iyr(Some("2011".into())).unwrap(), 2011);
-        assert_eq!(validate_iyr(Some("2010".into())).unwrap(), 2010);
-        assert_eq!(validate_iyr(Some("2020".into())).unwrap(), 2020);
         assert_eq!(
-            validate_iyr(Some("2009".into())).unwrap_err(),
-            PassportError {}
+            validate_iyr(Schema::puzzle(), Some("2011".into())).unwrap(),
+            2011
+        );
+        assert_eq!(
+            validate_iyr(Schema::puzzle(), Some("2010".into())).unwrap(),
+            2010
+        );
+        assert_eq!(
+            validate_iyr(Schema::puzzle(), Some("2020".into())).unwrap(),
+            2020
         );
         assert_eq!(
-            validate_iyr(Some("2021".into())).unwrap_err(),
-            PassportError {}
+            validate_iyr(Schema::puzzle(), Some("2009".into())).unwrap_err(),
+            PassportError::OutOfRange(Field::Iyr)
+        );
+        assert_eq!(
+            validate_iyr(Schema::puzzle(), Some("2021".into())).unwrap_err(),
+            PassportError::OutOfRange(Field::Iyr)
         );
     }
     #[test]
     fn validated_eyr() {
-        assert_eq!(validate_eyr(Some("2021".into())).unwrap(), 2021);
-        assert_eq!(validate_eyr(Some("2020".into())).unwrap(), 2020);
-        assert_eq!(validate_eyr(Some("2030".into())).unwrap(), 2030);
         assert_eq!(
-            validate_eyr(Some("2019".into())).unwrap_err(),
-            PassportError {}
+            validate_eyr(Schema::puzzle(), Some("2021".into())).unwrap(),
+            2021
+        );
+        assert_eq!(
+            validate_eyr(Schema::puzzle(), Some("2020".into())).unwrap(),
+            2020
+        );
+        assert_eq!(
+            validate_eyr(Schema::puzzle(), Some("2030".into())).unwrap(),
+            2030
+        );
+        assert_eq!(
+            validate_eyr(Schema::puzzle(), Some("2019".into())).unwrap_err(),
+            PassportError::OutOfRange(Field::Eyr)
         );
         assert_eq!(
-            validate_eyr(Some("2031".into())).unwrap_err(),
-            PassportError {}
+            validate_eyr(Schema::puzzle(), Some("2031".into())).unwrap_err(),
+            PassportError::OutOfRange(Field::Eyr)
         );
     }
     #[test]
     fn validated_hgt() {
-        assert_eq!(validate_hgt(Some("60in".into())).unwrap(), Height::Inch(60));
-        assert_eq!(validate_hgt(Some("190cm".into())).unwrap(), Height::Cm(190));
         assert_eq!(
-            validate_hgt(Some("190in".into())).unwrap_err(),
-            PassportError {}
+            validate_hgt(Schema::puzzle(), Some("60in".into())).unwrap(),
+            Height::Inch(60)
+        );
+        assert_eq!(
+            validate_hgt(Schema::puzzle(), Some("190cm".into())).unwrap(),
+            Height::Cm(190)
+        );
+        assert_eq!(
+            validate_hgt(Schema::puzzle(), Some("190in".into())).unwrap_err(),
+            PassportError::OutOfRange(Field::Hgt)
+        );
+        assert_eq!(
+            validate_hgt(Schema::puzzle(), Some("149cm".into())).unwrap_err(),
+            PassportError::OutOfRange(Field::Hgt)
+        );
+        assert_eq!(
+            validate_hgt(Schema::puzzle(), Some("1900mm".into())).unwrap_err(),
+            PassportError::BadUnit(Field::Hgt)
         );
         assert_eq!(
-            validate_hgt(Some("190".into())).unwrap_err(),
-            PassportError {}
+            validate_hgt(Schema::puzzle(), Some("190".into())).unwrap_err(),
+            PassportError::BadUnit(Field::Hgt)
         );
     }
     #[test]
     fn validated_hcl() {
-        assert_eq!(validate_hcl(Some("#123abc".into())).unwrap(), "#123abc");
         assert_eq!(
-            validate_hcl(Some("#123abz".into())).unwrap_err(),
-            PassportError {}
+            validate_hcl(Schema::puzzle(), Some("#123abc".into())).unwrap(),
+            Color::new(0x12, 0x3a, 0xbc)
+        );
+        assert_eq!(
+            validate_hcl(Schema::puzzle(), Some("#123abz".into())).unwrap_err(),
+            PassportError::BadFormat(Field::Hcl)
         );
         assert_eq!(
-            validate_hcl(Some("123abc".into())).unwrap_err(),
-            PassportError {}
+            validate_hcl(Schema::puzzle(), Some("123abc".into())).unwrap_err(),
+            PassportError::BadFormat(Field::Hcl)
         );
     }
     #[test]
     fn validated_ecl() {
-        assert_eq!(validate_ecl(Some("brn".into())).unwrap(), EyeColor::Brn);
         assert_eq!(
-            validate_ecl(Some("wat".into())).unwrap_err(),
-            PassportError {}
+            validate_ecl(Schema::puzzle(), Some("brn".into())).unwrap(),
+            EyeColor::Brn
+        );
+        assert_eq!("hzl".parse(), Ok(EyeColor::Hzl));
+        assert_eq!("HZL".parse::<EyeColor>(), Err(ParseEyeColorError));
+        assert_eq!(EyeColor::Oth.to_string(), "oth");
+        assert_eq!(
+            validate_ecl(Schema::puzzle(), Some("wat".into())).unwrap_err(),
+            PassportError::BadFormat(Field::Ecl)
         );
     }
     #[test]
     fn validated_pid() {
-        assert_eq!(validate_pid(Some("000000001".into())).unwrap(), "000000001");
         assert_eq!(
-            validate_hcl(Some("0123456789".into())).unwrap_err(),
-            PassportError {}
+            validate_pid(Schema::puzzle(), Some("000000001".into())).unwrap(),
+            "000000001"
+        );
+        assert_eq!(
+            validate_hcl(Schema::puzzle(), Some("0123456789".into())).unwrap_err(),
+            PassportError::BadFormat(Field::Hcl)
         );
     }
     #[test]
@@ -375,27 +1065,58 @@ iyr:2011 ecl:brn hgt:59in";
         let pstr = "eyr:1972 cid:100
 hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926";
         assert_eq!(
-            PassportError {},
+            PassportErrors(vec![
+                PassportError::OutOfRange(Field::Eyr),
+                PassportError::BadUnit(Field::Hgt),
+                PassportError::BadFormat(Field::Pid)
+            ]),
             ValidatedPassport::from_str(pstr).unwrap_err()
         );
         let pstr = "iyr:2019
 hcl:#602927 eyr:1967 hgt:170cm
 ecl:grn pid:012533040 byr:1946";
         assert_eq!(
-            PassportError {},
+            PassportErrors(vec![PassportError::OutOfRange(Field::Eyr)]),
             ValidatedPassport::from_str(pstr).unwrap_err()
         );
         let pstr = "hcl:dab227 iyr:2012
 ecl:brn hgt:182cm pid:021572410 eyr:2020 byr:1992 cid:277";
         assert_eq!(
-            PassportError {},
+            PassportErrors(vec![PassportError::BadFormat(Field::Hcl)]),
             ValidatedPassport::from_str(pstr).unwrap_err()
         );
         let pstr = "hgt:59cm ecl:zzz
 eyr:2038 hcl:74454a iyr:2023
 pid:3556412378 byr:2007";
         assert_eq!(
-            PassportError {},
+            PassportErrors(vec![
+                PassportError::OutOfRange(Field::Byr),
+                PassportError::OutOfRange(Field::Iyr),
+                PassportError::OutOfRange(Field::Eyr),
+                PassportError::OutOfRange(Field::Hgt),
+                PassportError::BadFormat(Field::Hcl),
+                PassportError::BadFormat(Field::Ecl),
+                PassportError::BadFormat(Field::Pid)
+            ]),
+            ValidatedPassport::from_str(pstr).unwrap_err()
+        );
+    }
+    #[test]
+    fn passports_invalid_keys_and_values() {
+        let pstr = "byr:2003 iyr:2015 eyr:2025 hgt:170cm hcl:#123abc ecl:brn pid:000000001 foo:bar";
+        assert_eq!(
+            PassportErrors(vec![
+                PassportError::UnknownKey("foo".into()),
+                PassportError::OutOfRange(Field::Byr)
+            ]),
+            ValidatedPassport::from_str(pstr).unwrap_err()
+        );
+        let pstr = "byr:1980 iyr eyr:2035 hgt:170cm hcl:#123abc ecl:brn pid:000000001";
+        assert_eq!(
+            PassportErrors(vec![
+                PassportError::BadFormat(Field::Iyr),
+                PassportError::OutOfRange(Field::Eyr)
+            ]),
             ValidatedPassport::from_str(pstr).unwrap_err()
         );
     }
@@ -409,10 +1130,11 @@ hcl:#623a2f";
                 issue_year: 2012,
                 expiration_year: 2030,
                 height: Height::Inch(74),
-                hair_color: "#623a2f".into(),
+                hair_color: "#623a2f".parse().unwrap(),
                 eye_color: EyeColor::Grn,
                 passport_id: "087499704".into(),
-                country_id: None
+                country_id: None,
+                extra: BTreeMap::new()
             },
             ValidatedPassport::from_str(pstr).unwrap()
         );
@@ -424,10 +1146,11 @@ iyr:2014 pid:896056539 hcl:#a97842 hgt:165cm";
                 issue_year: 2014,
                 expiration_year: 2029,
                 height: Height::Cm(165),
-                hair_color: "#a97842".into(),
+                hair_color: "#a97842".parse().unwrap(),
                 eye_color: EyeColor::Blu,
                 passport_id: "896056539".into(),
-                country_id: Some("129".into())
+                country_id: Some("129".into()),
+                extra: BTreeMap::new()
             },
             ValidatedPassport::from_str(pstr).unwrap()
         );
@@ -441,10 +1164,11 @@ eyr:2022";
                 issue_year: 2015,
                 expiration_year: 2022,
                 height: Height::Cm(164),
-                hair_color: "#888785".into(),
+                hair_color: "#888785".parse().unwrap(),
                 eye_color: EyeColor::Hzl,
                 passport_id: "545766238".into(),
-                country_id: Some("88".into())
+                country_id: Some("88".into()),
+                extra: BTreeMap::new()
             },
             ValidatedPassport::from_str(pstr).unwrap()
         );
@@ -455,10 +1179,11 @@ eyr:2022";
                 issue_year: 2010,
                 expiration_year: 2021,
                 height: Height::Cm(158),
-                hair_color: "#b6652a".into(),
+                hair_color: "#b6652a".parse().unwrap(),
                 eye_color: EyeColor::Blu,
                 passport_id: "093154719".into(),
-                country_id: None
+                country_id: None,
+                extra: BTreeMap::new()
             },
             ValidatedPassport::from_str(pstr).unwrap()
         );
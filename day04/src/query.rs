@@ -0,0 +1,321 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    num::NonZeroU32,
+    str::FromStr,
+};
+
+use records::records;
+
+use crate::{
+    height::Height,
+    passport::{Field, PassportError, PassportErrors, Record, ValidatedPassport},
+};
+
+// A whole batch file, split into the passports that validated and the records that didn't
+#[derive(Debug, Default)]
+pub struct Batch {
+    pub passports: Vec<ValidatedPassport>,
+    // Line the failing record starts on, and everything wrong with it
+    pub failures: Vec<(usize, PassportErrors)>,
+}
+
+impl Batch {
+    pub fn parse(input: &str) -> Batch {
+        let mut batch = Batch::default();
+        for record in records(input) {
+            match record.text.parse() {
+                Ok(passport) => batch.passports.push(passport),
+                Err(errors) => batch.failures.push((record.line, errors)),
+            }
+        }
+        batch
+    }
+
+    // Most common errors first, ties in the order of their message
+    pub fn top_failures(&self, n: usize) -> Vec<(PassportError, usize)> {
+        let mut counts: HashMap<&PassportError, usize> = HashMap::new();
+        for error in self.failures.iter().flat_map(|(_, errors)| &errors.0) {
+            *counts.entry(error).or_default() += 1;
+        }
+        let mut counts: Vec<_> = counts.into_iter().map(|(e, c)| (e.clone(), c)).collect();
+        counts.sort_by(|(a, a_count), (b, b_count)| {
+            b_count
+                .cmp(a_count)
+                .then_with(|| a.to_string().cmp(&b.to_string()))
+        });
+        counts.truncate(n);
+        counts
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum QueryError {
+    // Not of the form `field op value`
+    BadPredicate(String),
+    UnknownField(String),
+    BadValue(Field),
+    // `<` and the like on a field that's compared as text, where "88" would sort after "100"
+    Unordered(Field),
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryError::BadPredicate(s) => write!(f, "expected `field op value`, got {:?}", s),
+            QueryError::UnknownField(s) => write!(f, "unknown field {:?}", s),
+            QueryError::BadValue(field) => write!(f, "bad value to compare {} with", field),
+            QueryError::Unordered(field) => {
+                write!(f, "{} can only be compared with == or !=", field)
+            }
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+// A field's value in a form that compares sensibly, years as numbers and heights across units
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone)]
+pub enum Value {
+    Number(i64),
+    Height(Height),
+    Text(String),
+}
+
+impl Value {
+    fn parse(field: Field, s: &str) -> Result<Value, QueryError> {
+        Ok(match field {
+            Field::Byr | Field::Iyr | Field::Eyr => {
+                Value::Number(s.parse().map_err(|_| QueryError::BadValue(field))?)
+            }
+            Field::Hgt => Value::Height(s.parse().map_err(|_| QueryError::BadValue(field))?),
+            _ => Value::Text(s.to_string()),
+        })
+    }
+}
+
+pub fn value(passport: &ValidatedPassport, field: Field) -> Option<Value> {
+    Some(match field {
+        Field::Byr => Value::Number(passport.birth_year as i64),
+        Field::Iyr => Value::Number(passport.issue_year as i64),
+        Field::Eyr => Value::Number(passport.expiration_year as i64),
+        Field::Hgt => Value::Height(passport.height),
+        Field::Hcl => Value::Text(passport.hair_color.to_string()),
+        Field::Ecl => Value::Text(passport.eye_color.to_string()),
+        Field::Pid => Value::Text(passport.passport_id.clone()),
+        Field::Cid => Value::Text(passport.country_id.clone()?),
+    })
+}
+
+// e.g. `eyr < 2022` or `ecl == grn`, a passport without the field never matches
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Predicate {
+    pub field: Field,
+    pub op: Op,
+    pub value: Value,
+}
+
+impl Predicate {
+    pub fn matches(&self, passport: &ValidatedPassport) -> bool {
+        let value = match value(passport, self.field) {
+            Some(value) => value,
+            None => return false,
+        };
+        match self.op {
+            Op::Eq => value == self.value,
+            Op::Ne => value != self.value,
+            Op::Lt => value < self.value,
+            Op::Le => value <= self.value,
+            Op::Gt => value > self.value,
+            Op::Ge => value >= self.value,
+        }
+    }
+}
+
+impl FromStr for Predicate {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<_> = s.split_whitespace().collect();
+        let (field, op, value) = match parts.as_slice() {
+            [field, op, value] => (field, op, value),
+            _ => return Err(QueryError::BadPredicate(s.into())),
+        };
+        let field =
+            Field::from_key(field).ok_or_else(|| QueryError::UnknownField(field.to_string()))?;
+        let op = match *op {
+            "==" | "=" => Op::Eq,
+            "!=" => Op::Ne,
+            "<" => Op::Lt,
+            "<=" => Op::Le,
+            ">" => Op::Gt,
+            ">=" => Op::Ge,
+            _ => return Err(QueryError::BadPredicate(s.into())),
+        };
+        let value = Value::parse(field, value)?;
+        if let (Value::Text(_), Op::Lt | Op::Le | Op::Gt | Op::Ge) = (&value, op) {
+            return Err(QueryError::Unordered(field));
+        }
+        Ok(Predicate { field, op, value })
+    }
+}
+
+// Passports matching every one of `predicates`
+pub fn filter<'a>(
+    passports: &'a [ValidatedPassport],
+    predicates: &[Predicate],
+) -> Vec<&'a ValidatedPassport> {
+    passports
+        .iter()
+        .filter(|p| predicates.iter().all(|pred| pred.matches(p)))
+        .collect()
+}
+
+// How many passports have each value of `field`, keyed by the value as written in the batch
+pub fn group_by<'a, I>(passports: I, field: Field) -> BTreeMap<String, usize>
+where
+    I: IntoIterator<Item = &'a ValidatedPassport>,
+{
+    let mut groups = BTreeMap::new();
+    for passport in passports {
+        if let Some((_, value)) = passport.entries().into_iter().find(|(f, _)| *f == field) {
+            *groups.entry(value).or_default() += 1;
+        }
+    }
+    groups
+}
+
+// Counts of a numeric field in buckets `width` wide, keyed by the bucket's lowest value
+// Heights are counted in whole centimetres, fields that aren't numbers are skipped
+pub fn histogram<'a, I>(passports: I, field: Field, width: NonZeroU32) -> BTreeMap<i64, usize>
+where
+    I: IntoIterator<Item = &'a ValidatedPassport>,
+{
+    let width = i64::from(width.get());
+    let mut buckets = BTreeMap::new();
+    for passport in passports {
+        let n = match value(passport, field) {
+            Some(Value::Number(n)) => n,
+            Some(Value::Height(h)) => h.micrometres() / 10_000,
+            _ => continue,
+        };
+        *buckets.entry(n - n.rem_euclid(width)).or_default() += 1;
+    }
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "eyr:1972 cid:100
+hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926
+
+iyr:2019
+hcl:#602927 eyr:1967 hgt:170cm
+ecl:grn pid:012533040 byr:1946
+
+pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980
+hcl:#623a2f
+
+eyr:2029 ecl:blu cid:129 byr:1989
+iyr:2014 pid:896056539 hcl:#a97842 hgt:165cm
+
+hcl:#888785
+hgt:164cm byr:2001 iyr:2015 cid:88
+pid:545766238 ecl:hzl
+eyr:2022
+
+iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719";
+
+    #[test]
+    fn batch() {
+        let batch = Batch::parse(INPUT);
+        assert_eq!(batch.passports.len(), 4);
+        assert_eq!(batch.failures.len(), 2);
+        assert_eq!(batch.failures[1].0, 4);
+        assert_eq!(
+            batch.top_failures(2),
+            vec![
+                (PassportError::OutOfRange(Field::Eyr), 2),
+                (PassportError::BadUnit(Field::Hgt), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn predicates() {
+        let batch = Batch::parse(INPUT);
+        let eyr: Predicate = "eyr < 2022".parse().unwrap();
+        assert_eq!(
+            filter(&batch.passports, std::slice::from_ref(&eyr)).len(),
+            1
+        );
+        let blu: Predicate = "ecl == blu".parse().unwrap();
+        assert_eq!(
+            filter(&batch.passports, std::slice::from_ref(&blu)).len(),
+            2
+        );
+        assert_eq!(filter(&batch.passports, &[eyr, blu]).len(), 1);
+        let tall: Predicate = "hgt >= 1.65m".parse().unwrap();
+        assert_eq!(filter(&batch.passports, &[tall]).len(), 2);
+        let cid: Predicate = "cid != 88".parse().unwrap();
+        assert_eq!(filter(&batch.passports, &[cid]).len(), 1);
+
+        assert_eq!(
+            "eyr <".parse::<Predicate>(),
+            Err(QueryError::BadPredicate("eyr <".into()))
+        );
+        assert_eq!(
+            "foo == 1".parse::<Predicate>(),
+            Err(QueryError::UnknownField("foo".into()))
+        );
+        assert_eq!(
+            "byr > old".parse::<Predicate>(),
+            Err(QueryError::BadValue(Field::Byr))
+        );
+        assert_eq!(
+            "cid < 100".parse::<Predicate>(),
+            Err(QueryError::Unordered(Field::Cid))
+        );
+        assert_eq!(
+            "pid >= 100000000".parse::<Predicate>(),
+            Err(QueryError::Unordered(Field::Pid))
+        );
+    }
+
+    #[test]
+    fn groups_and_histograms() {
+        let ten = NonZeroU32::new(10).unwrap();
+        let batch = Batch::parse(INPUT);
+        let groups = group_by(&batch.passports, Field::Ecl);
+        assert_eq!(groups.get("blu"), Some(&2));
+        assert_eq!(groups.get("grn"), Some(&1));
+        assert_eq!(groups.get("hzl"), Some(&1));
+        let decades = histogram(&batch.passports, Field::Byr, ten);
+        assert_eq!(
+            decades.into_iter().collect::<Vec<_>>(),
+            vec![(1940, 1), (1980, 2), (2000, 1)]
+        );
+        let heights = histogram(&batch.passports, Field::Hgt, ten);
+        assert_eq!(heights.get(&150), Some(&1));
+        assert_eq!(heights.get(&160), Some(&2));
+        assert_eq!(heights.get(&180), Some(&1));
+        assert!(histogram(&batch.passports, Field::Ecl, ten).is_empty());
+        let years = histogram(&batch.passports, Field::Byr, NonZeroU32::new(1).unwrap());
+        assert_eq!(
+            years.keys().copied().collect::<Vec<_>>(),
+            vec![1944, 1980, 1989, 2001]
+        );
+    }
+}
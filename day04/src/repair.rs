@@ -0,0 +1,470 @@
+use std::fmt;
+
+use records::records;
+
+use crate::{
+    color::Color,
+    height,
+    passport::{
+        merge_errors, validate_field, DuplicateKeys, Field, ParseOptions, PassportBuilder,
+        PassportError, UnknownKeys,
+    },
+    schema::{Rule, Schema},
+};
+
+// What's wrong with one field and, where we can find one, a value that would pass
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Suggestion {
+    pub error: PassportError,
+    pub message: String,
+    pub fix: Option<String>,
+}
+
+impl fmt::Display for Suggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.fix, self.error.field()) {
+            (Some(fix), Some(field)) => {
+                write!(f, "{}; {}:{} would be valid", self.message, field, fix)
+            }
+            _ => f.write_str(&self.message),
+        }
+    }
+}
+
+fn suggestion(error: PassportError, message: String, fix: Option<String>) -> Suggestion {
+    Suggestion {
+        error,
+        message,
+        fix,
+    }
+}
+
+// First of `candidates` that passes `schema`
+fn first_valid<I: IntoIterator<Item = String>>(
+    schema: &Schema,
+    field: Field,
+    candidates: I,
+) -> Option<String> {
+    candidates
+        .into_iter()
+        .find(|c| validate_field(schema, field, Some(c)).is_ok())
+}
+
+// "between 1920 and 2002", or only the bound that's given
+fn bounds<T: fmt::Display>(min: Option<T>, max: Option<T>) -> Option<String> {
+    match (min, max) {
+        (Some(min), Some(max)) => Some(format!("between {} and {}", min, max)),
+        (Some(min), None) => Some(format!("at least {}", min)),
+        (None, Some(max)) => Some(format!("at most {}", max)),
+        (None, None) => None,
+    }
+}
+
+fn allowed_range(rule: &Rule) -> Option<String> {
+    match rule {
+        Rule::Number { min, max } => bounds(*min, *max),
+        Rule::Height { min, max, .. } => bounds(*min, *max),
+        Rule::Measure { units } => {
+            let ranges: Vec<_> = units
+                .iter()
+                .map(|(unit, (min, max))| format!("{}{} to {}{}", min, unit, max, unit))
+                .collect();
+            Some(ranges.join(" or "))
+        }
+        _ => None,
+    }
+}
+
+// Units a measured field can be written in, empty for any other rule
+fn units(rule: &Rule) -> Vec<&str> {
+    match rule {
+        Rule::Measure { units } => units.keys().map(String::as_str).collect(),
+        Rule::Height {
+            units: Some(units), ..
+        } => units.iter().map(String::as_str).collect(),
+        Rule::Height { units: None, .. } => height::UNITS.to_vec(),
+        _ => Vec::new(),
+    }
+}
+
+// Whether a charset is only digits, so messages can say "digits" instead of "characters"
+fn digits_only(charset: &str) -> bool {
+    charset.chars().all(|c| c.is_ascii_digit())
+}
+
+// "must be 9 digits" or "must match ^#[0-9a-f]{6}$", from what a text or enum rule checks
+fn format_rule(rule: &Rule) -> Option<String> {
+    match rule {
+        Rule::Text {
+            length,
+            charset,
+            pattern,
+        } => {
+            let mut parts = Vec::new();
+            match (length, charset) {
+                (Some(len), Some(set)) if digits_only(set) => {
+                    parts.push(format!("be {} digits", len))
+                }
+                (Some(len), Some(set)) => parts.push(format!("be {} of {}", len, set)),
+                (Some(len), None) => parts.push(format!("be {} characters", len)),
+                (None, Some(set)) => parts.push(format!("only contain {}", set)),
+                (None, None) => (),
+            }
+            if let Some(pattern) = pattern {
+                parts.push(format!("match {}", pattern.as_str()));
+            }
+            if parts.is_empty() {
+                return None;
+            }
+            Some(format!("must {}", parts.join(" and ")))
+        }
+        Rule::Enum { values } => Some(format!("must be one of {}", values.join(", "))),
+        _ => None,
+    }
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                prev
+            } else {
+                1 + prev.min(row[j]).min(row[j + 1])
+            };
+            prev = current;
+        }
+    }
+    row[b.len()]
+}
+
+fn suggest_for(schema: &Schema, error: PassportError, value: Option<&str>) -> Suggestion {
+    let field = match error.field() {
+        Some(field) => field,
+        None => {
+            let message = match &error {
+                PassportError::UnknownKey(key) => format!("remove unknown key {}", key),
+                PassportError::DuplicateUnknownKey(key) => {
+                    format!("unknown key {} is given more than once, remove it", key)
+                }
+                PassportError::MissingValue(key) => {
+                    format!(
+                        "unknown key {} has no value, write it as {}:value",
+                        key, key
+                    )
+                }
+                _ => error.to_string(),
+            };
+            return suggestion(error, message, None);
+        }
+    };
+    let value = match value {
+        Some(value) => value,
+        None => {
+            let message = match error {
+                PassportError::Missing(_) => {
+                    format!("{} is missing, it has to be filled in", field)
+                }
+                _ => format!("{} has no value, write it as {}:value", field, field),
+            };
+            return suggestion(error, message, None);
+        }
+    };
+    let rule = match schema.field(field) {
+        Some(field_schema) => &field_schema.rule,
+        None => {
+            let message = error.to_string();
+            return suggestion(error, message, None);
+        }
+    };
+    match (&error, rule) {
+        (PassportError::DuplicateKey(_), _) => {
+            let message = format!("{} is given more than once, keep only one", field);
+            suggestion(error, message, None)
+        }
+        (PassportError::NotANumber(_), _) => {
+            let digits: String = value.chars().filter(|c| c.is_ascii_digit()).collect();
+            let fix = first_valid(schema, field, Some(digits));
+            let message = format!("{} {} is not a number", field, value);
+            suggestion(error, message, fix)
+        }
+        (PassportError::BadUnit(_), _) => {
+            let units = units(rule);
+            let fix = first_valid(
+                schema,
+                field,
+                units.iter().map(|unit| format!("{}{}", value, unit)),
+            );
+            let message = if value.chars().all(|c| c.is_ascii_digit()) {
+                format!("{} {} missing unit", field, value)
+            } else {
+                format!(
+                    "{} {} has a unit other than {}",
+                    field,
+                    value,
+                    units.join(" or ")
+                )
+            };
+            suggestion(error, message, fix)
+        }
+        (PassportError::OutOfRange(_), _) => {
+            // a measurement in the wrong unit may be fine in another one
+            let number: String = value.chars().take_while(|c| c.is_ascii_digit()).collect();
+            let fix = first_valid(
+                schema,
+                field,
+                units(rule).iter().map(|unit| format!("{}{}", number, unit)),
+            );
+            let message = format!(
+                "{} {} must be {}",
+                field,
+                value,
+                allowed_range(rule).unwrap_or_default()
+            );
+            suggestion(error, message, fix)
+        }
+        (PassportError::BadFormat(_), Rule::Enum { values }) => {
+            // only worth suggesting when it looks like a typo
+            let lower = value.to_ascii_lowercase();
+            let closest = values
+                .iter()
+                .min_by_key(|v| edit_distance(&lower, v))
+                .filter(|v| edit_distance(&lower, v) <= 1)
+                .cloned();
+            let message = format!(
+                "{} {} {}",
+                field,
+                value,
+                format_rule(rule).unwrap_or_default()
+            );
+            suggestion(error, message, closest)
+        }
+        (
+            PassportError::BadFormat(_),
+            Rule::Text {
+                length, charset, ..
+            },
+        ) => {
+            let count = value.chars().count();
+            let in_charset = charset
+                .as_ref()
+                .is_none_or(|set| value.chars().all(|c| set.contains(c)));
+            let noun = match charset {
+                Some(set) if digits_only(set) => "digits",
+                _ => "characters",
+            };
+            let message = match (length, charset) {
+                (_, Some(set)) if !in_charset => {
+                    format!("{} {} must only contain {}", field, value, set)
+                }
+                (Some(len), _) if count != *len => {
+                    format!("{} has {} {}, it needs {}", field, count, noun, len)
+                }
+                _ => format!(
+                    "{} {} {}",
+                    field,
+                    value,
+                    format_rule(rule).unwrap_or_default()
+                ),
+            };
+            // common slips: a left out '#', upper case, short hex colours and lost leading zeros
+            let mut candidates = vec![
+                format!("#{}", value),
+                value.to_ascii_lowercase(),
+                format!("#{}", value.to_ascii_lowercase()),
+            ];
+            candidates.extend(value.parse::<Color>().ok().map(|c| c.to_string()));
+            if let Some(len) = length {
+                candidates.push(format!("{:0>1$}", value, len));
+            }
+            let fix = first_valid(schema, field, candidates);
+            suggestion(error, message, fix)
+        }
+        _ => {
+            let message = error.to_string();
+            suggestion(error, message, None)
+        }
+    }
+}
+
+// Everything wrong with one record against `schema` and how to fix it, empty if it's valid
+pub fn suggest_repairs(record: &str, schema: &Schema) -> Vec<Suggestion> {
+    // strict so repeated and unknown keys get reported, there's still a value to look at
+    let strict = ParseOptions {
+        duplicate_keys: DuplicateKeys::Reject,
+        unknown_keys: UnknownKeys::Reject,
+    };
+    let (builder, mut errors) = PassportBuilder::read(record, &strict);
+    let invalid = Field::ALL
+        .iter()
+        .filter_map(|field| validate_field(schema, *field, builder.value(*field)).err())
+        .collect();
+    merge_errors(&mut errors, invalid);
+    errors
+        .into_iter()
+        .map(|e| {
+            let value = e.field().and_then(|f| builder.value(f));
+            suggest_for(schema, e, value)
+        })
+        .collect()
+}
+
+// Suggestions for each invalid record in a batch, with the line the record starts on
+pub fn suggest_batch_repairs(input: &str, schema: &Schema) -> Vec<(usize, Vec<Suggestion>)> {
+    records(input)
+        .map(|r| (r.line, suggest_repairs(&r.text, schema)))
+        .filter(|(_, suggestions)| !suggestions.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(record: &str) -> Vec<String> {
+        suggest_repairs(record, Schema::puzzle())
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn valid_needs_nothing() {
+        let pstr = "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980 hcl:#623a2f";
+        assert!(suggest_repairs(pstr, Schema::puzzle()).is_empty());
+    }
+
+    #[test]
+    fn fixable_fields() {
+        let pstr = "eyr:1972 cid:100
+hcl:18171d ecl:grnn hgt:190 pid:1234567890 iyr:2018 byr:1x926";
+        assert_eq!(
+            messages(pstr),
+            vec![
+                "byr 1x926 is not a number; byr:1926 would be valid".to_string(),
+                "eyr 1972 must be between 2020 and 2030".into(),
+                "hgt 190 missing unit; hgt:190cm would be valid".into(),
+                "hcl 18171d must match ^#[0-9a-f]{6}$; hcl:#18171d would be valid".into(),
+                "ecl grnn must be one of amb, blu, brn, gry, grn, hzl, oth; ecl:grn would be valid"
+                    .into(),
+                "pid has 10 digits, it needs 9".into(),
+            ]
+        );
+    }
+
+    #[test]
+    fn other_fixes() {
+        let pstr = "byr:1980 iyr:2012 eyr:2030 hgt:74cm hcl:#ABC ecl:grn pid:12345";
+        assert_eq!(
+            messages(pstr),
+            vec![
+                "hgt 74cm must be between 59in and 76in; hgt:74in would be valid".to_string(),
+                "hcl #ABC must match ^#[0-9a-f]{6}$; hcl:#aabbcc would be valid".into(),
+                "pid has 5 digits, it needs 9; pid:000012345 would be valid".into(),
+            ]
+        );
+        let pstr = "byr:1980 iyr:2012 eyr:2030 hgt:74in hcl:#623a2f ecl:#18f71a pid:087499704";
+        assert_eq!(
+            messages(pstr),
+            vec!["ecl #18f71a must be one of amb, blu, brn, gry, grn, hzl, oth".to_string()]
+        );
+        let pstr = "byr:1980 iyr:2012 eyr:2030 hgt:74in hcl:#623a2f ecl:BRN pid:087499704";
+        assert_eq!(
+            messages(pstr),
+            vec![
+                "ecl BRN must be one of amb, blu, brn, gry, grn, hzl, oth; ecl:brn would be valid"
+                    .to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn bad_keys() {
+        let pstr = "byr:1980 byr:1981 iyr:2012 eyr:2030 hgt:74in hcl:#623a2f ecl:grn foo:bar";
+        assert_eq!(
+            messages(pstr),
+            vec![
+                "byr is given more than once, keep only one".to_string(),
+                "remove unknown key foo".into(),
+                "pid is missing, it has to be filled in".into(),
+            ]
+        );
+    }
+
+    #[test]
+    fn key_and_value_errors() {
+        let pstr = "byr:1850 iyr:2012 eyr:2030 hgt:74in hcl:#623a2f ecl:grn pid:087499704 cid";
+        assert_eq!(
+            messages(pstr),
+            vec![
+                "cid has no value, write it as cid:value".to_string(),
+                "byr 1850 must be between 1920 and 2002".into(),
+            ]
+        );
+    }
+
+    #[test]
+    fn other_schema() {
+        let schema = Schema::from_toml(
+            r#"
+            [fields.hgt]
+            type = "measure"
+            units = { mm = [1400, 2100] }
+
+            [fields.hcl]
+            type = "text"
+            length = 4
+            charset = "abcdef"
+
+            [fields.pid]
+            type = "text"
+            length = 6
+            charset = "0123456789"
+            "#,
+        )
+        .unwrap();
+        let suggestions = |record| -> Vec<String> {
+            suggest_repairs(record, &schema)
+                .iter()
+                .map(|s| s.to_string())
+                .collect()
+        };
+        assert_eq!(
+            suggestions("hgt:1800 hcl:abcg pid:1234"),
+            vec![
+                "hgt 1800 missing unit; hgt:1800mm would be valid".to_string(),
+                "hcl abcg must only contain abcdef".into(),
+                "pid has 4 digits, it needs 6; pid:001234 would be valid".into(),
+            ]
+        );
+        assert_eq!(
+            suggestions("hgt:2200mm hcl:ABCD pid:123456"),
+            vec![
+                "hgt 2200mm must be 1400mm to 2100mm".to_string(),
+                "hcl ABCD must only contain abcdef; hcl:abcd would be valid".into(),
+            ]
+        );
+        assert_eq!(
+            suggestions("hgt:180cm hcl:abc pid:123456"),
+            vec![
+                "hgt 180cm has a unit other than mm".to_string(),
+                "hcl has 3 characters, it needs 4".into(),
+            ]
+        );
+    }
+
+    #[test]
+    fn batch() {
+        let input = "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980 hcl:#623a2f
+
+pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980";
+        let repairs = suggest_batch_repairs(input, Schema::puzzle());
+        assert_eq!(repairs.len(), 1);
+        assert_eq!(repairs[0].0, 3);
+        assert_eq!(repairs[0].1[0].error, PassportError::Missing(Field::Hcl));
+    }
+}
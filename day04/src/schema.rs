@@ -0,0 +1,314 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+    fmt,
+    sync::OnceLock,
+};
+
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::{
+    height::{Height, HeightError},
+    passport::{Field, PassportError},
+};
+
+// Validation rules for passport fields, loaded from TOML or JSON so they can differ per country
+// Fields that aren't listed accept any value and may be left out
+#[derive(Debug, Clone, Deserialize)]
+pub struct Schema {
+    fields: HashMap<Field, FieldSchema>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FieldSchema {
+    #[serde(default = "required_default")]
+    pub required: bool,
+    #[serde(flatten)]
+    pub rule: Rule,
+}
+
+fn required_default() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Rule {
+    // Whole number, both bounds inclusive
+    Number {
+        min: Option<i64>,
+        max: Option<i64>,
+    },
+    // Whole number directly followed by a unit, each unit with its own inclusive range
+    Measure {
+        units: BTreeMap<String, (i64, i64)>,
+    },
+    // Any unit `Height` understands, limited to `units` if given
+    // The range is a single one in any unit, e.g. min = "59in", max = "193cm"
+    Height {
+        units: Option<Vec<String>>,
+        min: Option<Height>,
+        max: Option<Height>,
+    },
+    Text {
+        length: Option<usize>,
+        charset: Option<String>,
+        pattern: Option<Pattern>,
+    },
+    Enum {
+        values: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Pattern(Regex);
+
+impl Pattern {
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl TryFrom<String> for Pattern {
+    type Error = regex::Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Regex::new(&s).map(Pattern)
+    }
+}
+
+#[derive(Debug)]
+pub enum SchemaError {
+    Toml(toml::de::Error),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::Toml(e) => write!(f, "bad TOML schema: {}", e),
+            SchemaError::Json(e) => write!(f, "bad JSON schema: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+impl Schema {
+    pub fn from_toml(s: &str) -> Result<Schema, SchemaError> {
+        toml::from_str(s).map_err(SchemaError::Toml)
+    }
+
+    pub fn from_json(s: &str) -> Result<Schema, SchemaError> {
+        serde_json::from_str(s).map_err(SchemaError::Json)
+    }
+
+    // The rules from the puzzle, parsed once from `schema/puzzle.toml`
+    pub fn puzzle() -> &'static Schema {
+        static PUZZLE: OnceLock<Schema> = OnceLock::new();
+        PUZZLE.get_or_init(|| {
+            Schema::from_toml(include_str!("../schema/puzzle.toml"))
+                .expect("built in puzzle schema is broken")
+        })
+    }
+
+    pub fn field(&self, field: Field) -> Option<&FieldSchema> {
+        self.fields.get(&field)
+    }
+
+    pub fn check(&self, field: Field, value: Option<&str>) -> Result<(), PassportError> {
+        match self.fields.get(&field) {
+            Some(schema) => schema.check(field, value),
+            None => Ok(()),
+        }
+    }
+}
+
+impl FieldSchema {
+    pub fn check(&self, field: Field, value: Option<&str>) -> Result<(), PassportError> {
+        match value {
+            Some(value) => self.rule.check(field, value),
+            None if self.required => Err(PassportError::Missing(field)),
+            None => Ok(()),
+        }
+    }
+}
+
+// Splits "183cm" into ("183", "cm")
+fn split_measure(value: &str) -> (&str, &str) {
+    let at = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    value.split_at(at)
+}
+
+fn check_range(
+    field: Field,
+    i: i64,
+    min: Option<i64>,
+    max: Option<i64>,
+) -> Result<(), PassportError> {
+    if min.is_some_and(|min| i < min) || max.is_some_and(|max| i > max) {
+        return Err(PassportError::OutOfRange(field));
+    }
+    Ok(())
+}
+
+impl Rule {
+    pub fn check(&self, field: Field, value: &str) -> Result<(), PassportError> {
+        match self {
+            Rule::Number { min, max } => {
+                let i: i64 = value
+                    .parse()
+                    .map_err(|_| PassportError::NotANumber(field))?;
+                check_range(field, i, *min, *max)
+            }
+            Rule::Measure { units } => {
+                let (number, unit) = split_measure(value);
+                let (min, max) = units.get(unit).ok_or(PassportError::BadUnit(field))?;
+                let i: i64 = number
+                    .parse()
+                    .map_err(|_| PassportError::NotANumber(field))?;
+                check_range(field, i, Some(*min), Some(*max))
+            }
+            Rule::Height { units, min, max } => {
+                let height: Height = value.parse().map_err(|e| match e {
+                    HeightError::NotANumber => PassportError::NotANumber(field),
+                    HeightError::BadUnit => PassportError::BadUnit(field),
+                    HeightError::OutOfRange => PassportError::OutOfRange(field),
+                })?;
+                if units
+                    .as_ref()
+                    .is_some_and(|units| !units.iter().any(|u| u == height.unit()))
+                {
+                    return Err(PassportError::BadUnit(field));
+                }
+                if min.is_some_and(|min| height < min) || max.is_some_and(|max| height > max) {
+                    return Err(PassportError::OutOfRange(field));
+                }
+                Ok(())
+            }
+            Rule::Text {
+                length,
+                charset,
+                pattern,
+            } => {
+                let bad_length = length.is_some_and(|len| value.chars().count() != len);
+                let bad_chars = charset
+                    .as_ref()
+                    .is_some_and(|set| !value.chars().all(|c| set.contains(c)));
+                let bad_pattern = pattern.as_ref().is_some_and(|p| !p.0.is_match(value));
+                if bad_length || bad_chars || bad_pattern {
+                    return Err(PassportError::BadFormat(field));
+                }
+                Ok(())
+            }
+            Rule::Enum { values } => {
+                if !values.iter().any(|v| v == value) {
+                    return Err(PassportError::BadFormat(field));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn puzzle_schema() {
+        let schema = Schema::puzzle();
+        assert_eq!(schema.check(Field::Byr, Some("2002")), Ok(()));
+        assert_eq!(
+            schema.check(Field::Byr, Some("2003")),
+            Err(PassportError::OutOfRange(Field::Byr))
+        );
+        assert_eq!(
+            schema.check(Field::Hgt, Some("190mm")),
+            Err(PassportError::BadUnit(Field::Hgt))
+        );
+        assert_eq!(
+            schema.check(Field::Hgt, Some("149cm")),
+            Err(PassportError::OutOfRange(Field::Hgt))
+        );
+        assert_eq!(
+            schema.check(Field::Hcl, Some("#12345g")),
+            Err(PassportError::BadFormat(Field::Hcl))
+        );
+        assert_eq!(schema.check(Field::Cid, None), Ok(()));
+        assert_eq!(
+            schema.check(Field::Pid, None),
+            Err(PassportError::Missing(Field::Pid))
+        );
+    }
+
+    #[test]
+    fn json_schema() {
+        let schema = Schema::from_json(
+            r#"{
+                "fields": {
+                    "hgt": { "type": "measure", "units": { "mm": [1400, 2100] } },
+                    "ecl": { "type": "enum", "values": ["blu"], "required": false },
+                    "cid": { "type": "text", "length": 3, "charset": "0123456789" }
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(schema.check(Field::Hgt, Some("1800mm")), Ok(()));
+        assert_eq!(
+            schema.check(Field::Hgt, Some("180cm")),
+            Err(PassportError::BadUnit(Field::Hgt))
+        );
+        assert_eq!(schema.check(Field::Ecl, None), Ok(()));
+        assert_eq!(
+            schema.check(Field::Cid, None),
+            Err(PassportError::Missing(Field::Cid))
+        );
+        assert_eq!(
+            schema.check(Field::Cid, Some("12a")),
+            Err(PassportError::BadFormat(Field::Cid))
+        );
+        // not in the schema, anything goes
+        assert_eq!(schema.check(Field::Byr, Some("whenever")), Ok(()));
+    }
+
+    #[test]
+    fn height_schema() {
+        let schema = Schema::from_toml(
+            r#"
+            [fields.hgt]
+            type = "height"
+            min = "1.5m"
+            max = "6'6\""
+            "#,
+        )
+        .unwrap();
+        assert_eq!(schema.check(Field::Hgt, Some("1500mm")), Ok(()));
+        assert_eq!(schema.check(Field::Hgt, Some("5'9\"")), Ok(()));
+        assert_eq!(schema.check(Field::Hgt, Some("198cm")), Ok(()));
+        assert_eq!(
+            schema.check(Field::Hgt, Some("199cm")),
+            Err(PassportError::OutOfRange(Field::Hgt))
+        );
+        assert_eq!(
+            schema.check(Field::Hgt, Some("1.4m")),
+            Err(PassportError::OutOfRange(Field::Hgt))
+        );
+        assert_eq!(
+            schema.check(Field::Hgt, Some("1.4x")),
+            Err(PassportError::BadUnit(Field::Hgt))
+        );
+        assert!(Schema::from_toml("[fields.hgt]\ntype = \"height\"\nmin = \"tall\"").is_err());
+    }
+
+    #[test]
+    fn bad_schema() {
+        assert!(Schema::from_toml("[fields.byr]\ntype = \"date\"").is_err());
+        assert!(Schema::from_toml("[fields.hcl]\ntype = \"text\"\npattern = \"(\"").is_err());
+        assert!(Schema::from_toml("[fields.xyz]\ntype = \"text\"").is_err());
+    }
+}
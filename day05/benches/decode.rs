@@ -0,0 +1,36 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use day05::seat::{decode_by_halving, decode_with_scheme, encode_seat, Scheme};
+
+fn passes() -> Vec<String> {
+    (0..128)
+        .flat_map(|row| (0..8).map(move |column| encode_seat(row, column)))
+        .collect()
+}
+
+fn decode(c: &mut Criterion) {
+    let passes = passes();
+    let mut group = c.benchmark_group("decode all 1024 seats");
+    group.bench_function("bitwise", |b| {
+        b.iter(|| {
+            for pass in &passes {
+                black_box(decode_with_scheme(black_box(pass), &Scheme::PUZZLE));
+            }
+        })
+    });
+    group.bench_function("halving", |b| {
+        b.iter(|| {
+            for pass in &passes {
+                black_box(decode_by_halving(black_box(pass), &Scheme::PUZZLE));
+            }
+        })
+    });
+    group.finish();
+}
+
+fn encode(c: &mut Criterion) {
+    c.bench_function("encode all 1024 seats", |b| b.iter(|| black_box(passes())));
+}
+
+criterion_group!(benches, decode, encode);
+criterion_main!(benches);
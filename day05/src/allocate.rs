@@ -0,0 +1,137 @@
+use crate::{
+    seat::{Scheme, Seat},
+    seat_map::SeatMap,
+};
+
+// Where a party can sit
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Allocation {
+    // Side by side in one row
+    Together(Vec<Seat>),
+    // No row has room for everyone, these are the empty seats closest to each other
+    Split(Vec<Seat>),
+}
+
+impl Allocation {
+    pub fn seats(&self) -> &[Seat] {
+        match self {
+            Allocation::Together(seats) | Allocation::Split(seats) => seats,
+        }
+    }
+
+    // The boarding passes to print for the party
+    pub fn passes(&self, scheme: &Scheme) -> Vec<String> {
+        self.seats()
+            .iter()
+            .map(|seat| scheme.encode(seat.row, seat.column))
+            .collect()
+    }
+}
+
+// The front-most row with `party` empty seats in a run, leftmost run first
+// The aisle doesn't break a run
+fn together(map: &SeatMap, party: usize) -> Option<Vec<Seat>> {
+    let scheme = map.scheme();
+    for row in 0..scheme.rows() {
+        let mut run = 0;
+        for column in 0..scheme.columns() {
+            if map.is_occupied(row, column) {
+                run = 0;
+                continue;
+            }
+            run += 1;
+            if run == party {
+                let first = column + 1 - party as i32;
+                return Some(
+                    (first..=column)
+                        .map(|column| Seat::new(row, column, scheme))
+                        .collect(),
+                );
+            }
+        }
+    }
+    None
+}
+
+// The `party` empty seats spanning the fewest seat ids, front-most if there's a tie
+fn nearest(map: &SeatMap, party: usize) -> Option<Vec<Seat>> {
+    map.empty_seats()
+        .windows(party)
+        .min_by_key(|seats| seats[party - 1].id - seats[0].id)
+        .map(|seats| seats.to_vec())
+}
+
+// `None` for an empty party or when there aren't enough empty seats
+pub fn allocate(map: &SeatMap, party: usize) -> Option<Allocation> {
+    if party == 0 {
+        return None;
+    }
+    together(map, party)
+        .map(Allocation::Together)
+        .or_else(|| nearest(map, party).map(Allocation::Split))
+}
+
+// Allocates and marks the seats taken
+pub fn book(map: &mut SeatMap, party: usize) -> Option<Allocation> {
+    let allocation = allocate(map, party)?;
+    for seat in allocation.seats() {
+        map.occupy(*seat);
+    }
+    Some(allocation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seat_map::tests::small_cabin;
+
+    #[test]
+    fn together_front_first() {
+        let map = small_cabin();
+        let scheme = *map.scheme();
+        let allocation = allocate(&map, 2).unwrap();
+        assert_eq!(
+            allocation,
+            Allocation::Together(vec![Seat::new(2, 0, &scheme), Seat::new(2, 1, &scheme)])
+        );
+        assert_eq!(allocation.passes(&scheme), vec!["BFLL", "BFLR"]);
+        assert_eq!(
+            allocate(&map, 1),
+            Some(Allocation::Together(vec![Seat::new(0, 2, &scheme)]))
+        );
+        assert_eq!(allocate(&map, 0), None);
+    }
+
+    #[test]
+    fn split_when_no_row_fits() {
+        let map = small_cabin();
+        let scheme = *map.scheme();
+        assert_eq!(
+            allocate(&map, 4),
+            Some(Allocation::Split(vec![
+                Seat::new(2, 0, &scheme),
+                Seat::new(2, 1, &scheme),
+                Seat::new(2, 2, &scheme),
+                Seat::new(3, 1, &scheme),
+            ]))
+        );
+        assert_eq!(allocate(&map, 7), None);
+    }
+
+    #[test]
+    fn booking_fills_the_map() {
+        let mut map = SeatMap::new(Scheme::PUZZLE);
+        let first = book(&mut map, 3).unwrap();
+        assert_eq!(
+            first.passes(map.scheme()),
+            vec!["FFFFFFFLLL", "FFFFFFFLLR", "FFFFFFFLRL"]
+        );
+        let second = book(&mut map, 6).unwrap();
+        assert_eq!(second.seats()[0].row, 1);
+        assert_eq!(map.occupied_count(), 9);
+        for pass in first.passes(map.scheme()) {
+            let seat = pass.parse::<Seat>().unwrap();
+            assert!(map.is_occupied(seat.row, seat.column));
+        }
+    }
+}
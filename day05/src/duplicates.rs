@@ -0,0 +1,129 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::seat::{ParseSeatError, Scheme, Seat};
+
+// A seat that more than one boarding pass decodes to, with each pass and the line it's on
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Conflict {
+    pub seat: Seat,
+    pub passes: Vec<(usize, String)>,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "seat {} (row {}, column {}) is on {} passes:",
+            self.seat.id,
+            self.seat.row,
+            self.seat.column,
+            self.passes.len()
+        )?;
+        for (i, (line, pass)) in self.passes.iter().enumerate() {
+            let separator = if i == 0 { " " } else { ", " };
+            write!(f, "{}line {} {}", separator, line, pass)?;
+        }
+        Ok(())
+    }
+}
+
+// A pass that doesn't decode, lines count from 1
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct BadPass {
+    pub line: usize,
+    pub error: ParseSeatError,
+}
+
+impl fmt::Display for BadPass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl std::error::Error for BadPass {}
+
+pub fn find_duplicates(passes: &str) -> Result<Vec<Conflict>, BadPass> {
+    find_duplicates_with_scheme(passes, &Scheme::PUZZLE)
+}
+
+// Conflicts come in seat id order, blank lines are skipped
+pub fn find_duplicates_with_scheme(
+    passes: &str,
+    scheme: &Scheme,
+) -> Result<Vec<Conflict>, BadPass> {
+    let mut by_id: BTreeMap<i32, Conflict> = BTreeMap::new();
+    for (i, pass) in passes.lines().enumerate() {
+        let pass = pass.trim();
+        if pass.is_empty() {
+            continue;
+        }
+        let seat = scheme
+            .decode(pass)
+            .map_err(|error| BadPass { line: i + 1, error })?;
+        by_id
+            .entry(seat.id)
+            .or_insert_with(|| Conflict {
+                seat,
+                passes: Vec::new(),
+            })
+            .passes
+            .push((i + 1, pass.to_string()));
+    }
+    Ok(by_id
+        .into_values()
+        .filter(|conflict| conflict.passes.len() > 1)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicates() {
+        let passes = "BBFFBBFRLL
+FBFBBFFRLR
+BFFFBBFRRR
+
+FBFBBFFRLR
+BBFFBBFRLL
+FBFBBFFRLR
+";
+        let conflicts = find_duplicates(passes).unwrap();
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(
+            conflicts[0],
+            Conflict {
+                seat: "FBFBBFFRLR".parse().unwrap(),
+                passes: vec![
+                    (2, "FBFBBFFRLR".to_string()),
+                    (5, "FBFBBFFRLR".into()),
+                    (7, "FBFBBFFRLR".into())
+                ]
+            }
+        );
+        assert_eq!(
+            conflicts[1].to_string(),
+            "seat 820 (row 102, column 4) is on 2 passes: line 1 BBFFBBFRLL, line 6 BBFFBBFRLL"
+        );
+        assert!(find_duplicates("FBFBBFFRLR\nBFFFBBFRRR")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn bad_pass() {
+        let err = find_duplicates("FBFBBFFRLR\nFBFBBFFRLX").unwrap_err();
+        assert_eq!(
+            err,
+            BadPass {
+                line: 2,
+                error: ParseSeatError::BadColumnChar(9, 'X')
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "line 2: 'X' at 9 is not a column character"
+        );
+    }
+}
@@ -0,0 +1,50 @@
+// Runs of numbers missing between the smallest and largest in `list`, as (start, length)
+// Duplicates don't matter and numbers outside the list's range are never a gap
+pub fn find_gaps(list: &[i32]) -> Vec<(i32, u32)> {
+    let mut sorted = list.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    sorted
+        .windows(2)
+        .filter(|w| w[1].abs_diff(w[0]) > 1)
+        .map(|w| (w[0] + 1, w[1].abs_diff(w[0]) - 1))
+        .collect()
+}
+
+// The number missing between two others, `None` unless there's exactly one such number
+pub fn find_missing_in_list(list: Vec<i32>) -> Option<i32> {
+    let mut single = find_gaps(&list)
+        .into_iter()
+        .filter(|(_, length)| *length == 1)
+        .map(|(start, _)| start);
+    match (single.next(), single.next()) {
+        (Some(missing), None) => Some(missing),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gaps() {
+        assert_eq!(find_gaps(&[2, 3, 4, 6, 7]), vec![(5, 1)]);
+        assert_eq!(find_gaps(&[10, 1, 2, 5, 5, 6]), vec![(3, 2), (7, 3)]);
+        assert!(find_gaps(&[3, 2, 4, 4]).is_empty());
+        assert!(find_gaps(&[]).is_empty());
+        assert!(find_gaps(&[i32::MIN, i32::MIN + 1]).is_empty());
+        assert_eq!(
+            find_gaps(&[i32::MAX, i32::MIN]),
+            vec![(i32::MIN + 1, u32::MAX - 1)]
+        );
+    }
+
+    #[test]
+    fn only_single_gaps_count() {
+        // a run of two missing isn't "a seat between two occupied ones"
+        assert_eq!(find_missing_in_list(vec![1, 2, 5, 7, 8]), Some(6));
+        assert_eq!(find_missing_in_list(vec![1, 2, 5, 6]), None);
+        assert_eq!(find_missing_in_list(vec![]), None);
+    }
+}
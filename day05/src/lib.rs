@@ -0,0 +1,5 @@
+pub mod allocate;
+pub mod duplicates;
+pub mod gaps;
+pub mod seat;
+pub mod seat_map;
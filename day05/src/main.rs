@@ -1,59 +1,50 @@
-use std::fs::read_to_string;
+use std::{env, fs::read_to_string};
+
+use day05::{
+    allocate::allocate, duplicates::find_duplicates, gaps::find_missing_in_list,
+    seat::decode_boarding_pass, seat_map::SeatMap,
+};
 
 fn main() {
+    let args: Vec<String> = env::args().collect();
     let passes = read_to_string("input.txt").expect("error reading input file");
     println!("Part 1: ");
     println!("Max seat id: {}", max_seat_id(&passes));
     println!("Part 2: ");
     println!("My seat is: {}", find_empty_seat_id(&passes).unwrap());
-}
 
-#[derive(Debug, Eq, PartialEq, Copy, Clone)]
-struct Seat {
-    row: i32,
-    column: i32,
-    id: i32,
-}
+    // `--map` draws the cabin with every seat that has a pass
+    if args.iter().any(|a| a == "--map") {
+        let map = SeatMap::from_passes(&passes).expect("bad boarding pass");
+        print!("{}", map);
+        println!("Fully booked rows: {:?}", map.fully_booked_rows());
+    }
 
-fn decode_boarding_pass(pass: &str) -> Seat {
-    // This algorithm is obtuse and inefficient in Rust
-    // But, hey, it works!
-    // And it was fun to figure out how to make it work
-    fn range_binary_search(
-        range: Box<dyn ExactSizeIterator<Item = u16>>,
-        c: char,
-        left_char: char,
-        right_char: char,
-    ) -> Box<dyn ExactSizeIterator<Item = u16>> {
-        let len = range.len();
-        if c == left_char {
-            Box::new(range.take(len / 2))
-        } else if c == right_char {
-            Box::new(range.skip(len / 2))
-        } else {
-            panic!("Bad encoding")
+    // `--duplicates` checks that no two passes are for the same seat
+    if args.iter().any(|a| a == "--duplicates") {
+        match find_duplicates(&passes) {
+            Ok(conflicts) if conflicts.is_empty() => println!("No seat is on more than one pass"),
+            Ok(conflicts) => {
+                for conflict in conflicts {
+                    println!("{}", conflict);
+                }
+            }
+            Err(bad) => println!("{}", bad),
+        }
+    }
+
+    // `--book <n>` finds seats for a party of n among the empty ones
+    if let Some(pos) = args.iter().position(|a| a == "--book") {
+        let party: usize = args
+            .get(pos + 1)
+            .and_then(|n| n.parse().ok())
+            .expect("--book needs a party size");
+        let map = SeatMap::from_passes(&passes).expect("bad boarding pass");
+        match allocate(&map, party) {
+            Some(allocation) => println!("{:?}", allocation.passes(map.scheme())),
+            None => println!("No room for a party of {}", party),
         }
     }
-    let row = pass
-        .chars()
-        .take(7)
-        .fold(
-            Box::new(0..=127u16) as Box<dyn ExactSizeIterator<Item = u16>>,
-            |range, c| range_binary_search(range, c, 'F', 'B'),
-        )
-        .next()
-        .unwrap() as i32;
-    let column = pass
-        .chars()
-        .skip(7)
-        .fold(
-            Box::new(0..=7u16) as Box<dyn ExactSizeIterator<Item = u16>>,
-            |range, c| range_binary_search(range, c, 'L', 'R'),
-        )
-        .next()
-        .unwrap() as i32;
-    let id = row * 8 + column;
-    Seat { row, column, id }
 }
 
 fn max_seat_id(passes: &str) -> i32 {
@@ -70,27 +61,10 @@ fn find_empty_seat_id(passes: &str) -> Option<i32> {
     find_missing_in_list(ids)
 }
 
-fn find_missing_in_list(mut list: Vec<i32>) -> Option<i32> {
-    list.sort_unstable();
-    let mut prev: Option<i32> = None;
-    let mut found: Option<i32> = None;
-    for current in list {
-        if let Some(prev) = prev {
-            if current - 1 != prev {
-                if found.is_some() {
-                    panic!("multiple numbers missing");
-                }
-                found = Some(prev + 1);
-            }
-        }
-        prev = Some(current);
-    }
-    found
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use day05::seat::Seat;
 
     #[test]
     fn examples() {
@@ -144,8 +118,7 @@ mod tests {
         assert!(find_missing_in_list(vec![2, 3, 4, 6, 5]).is_none());
     }
     #[test]
-    #[should_panic(expected = "multiple numbers missing")]
     fn multiple_missing_numbers() {
-        find_missing_in_list(vec![7, 3, 6, 9, 4, 2]);
+        assert!(find_missing_in_list(vec![7, 3, 6, 9, 4, 2]).is_none());
     }
 }
@@ -0,0 +1,428 @@
+use std::{fmt, str::FromStr};
+
+// How a boarding pass spells out a seat: `row_bits` characters narrowing down the row,
+// then `column_bits` characters narrowing down the column
+// The first character of each pair keeps the lower half of what's left, the second the upper
+// Fields are only set through `new` and the `with_` methods, which keep seat ids in an `i32`
+// and the two characters of a pair apart
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Scheme {
+    row_bits: u32,
+    column_bits: u32,
+    row_chars: (char, char),
+    column_chars: (char, char),
+}
+
+impl Scheme {
+    // 128 rows of 8 seats, "FBFBBFFRLR"
+    pub const PUZZLE: Scheme = Scheme {
+        row_bits: 7,
+        column_bits: 3,
+        row_chars: ('F', 'B'),
+        column_chars: ('L', 'R'),
+    };
+
+    pub fn new(row_bits: u32, column_bits: u32) -> Scheme {
+        assert!(
+            row_bits + column_bits < 31,
+            "seat ids have to fit in 30 bits"
+        );
+        Scheme {
+            row_bits,
+            column_bits,
+            ..Scheme::PUZZLE
+        }
+    }
+
+    pub fn with_row_chars(mut self, front: char, back: char) -> Scheme {
+        assert_ne!(
+            front, back,
+            "front and back have to be different characters"
+        );
+        self.row_chars = (front, back);
+        self
+    }
+
+    pub fn with_column_chars(mut self, left: char, right: char) -> Scheme {
+        assert_ne!(
+            left, right,
+            "left and right have to be different characters"
+        );
+        self.column_chars = (left, right);
+        self
+    }
+
+    pub fn row_bits(&self) -> u32 {
+        self.row_bits
+    }
+
+    pub fn column_bits(&self) -> u32 {
+        self.column_bits
+    }
+
+    pub fn row_chars(&self) -> (char, char) {
+        self.row_chars
+    }
+
+    pub fn column_chars(&self) -> (char, char) {
+        self.column_chars
+    }
+
+    pub fn rows(&self) -> i32 {
+        1 << self.row_bits
+    }
+
+    pub fn columns(&self) -> i32 {
+        1 << self.column_bits
+    }
+
+    pub fn pass_len(&self) -> usize {
+        (self.row_bits + self.column_bits) as usize
+    }
+
+    // The boarding pass for a seat, the inverse of `decode`
+    pub fn encode(&self, row: i32, column: i32) -> String {
+        assert!(
+            (0..self.rows()).contains(&row) && (0..self.columns()).contains(&column),
+            "no seat at row {} column {}",
+            row,
+            column
+        );
+        fn bits(value: i32, count: u32, (zero, one): (char, char)) -> impl Iterator<Item = char> {
+            (0..count)
+                .rev()
+                .map(move |bit| if value >> bit & 1 == 0 { zero } else { one })
+        }
+        bits(row, self.row_bits, self.row_chars)
+            .chain(bits(column, self.column_bits, self.column_chars))
+            .collect()
+    }
+
+    // Seats are numbered front to back, left to right
+    pub fn seat_id(&self, row: i32, column: i32) -> i32 {
+        row * self.columns() + column
+    }
+}
+
+impl Default for Scheme {
+    fn default() -> Scheme {
+        Scheme::PUZZLE
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Seat {
+    pub row: i32,
+    pub column: i32,
+    pub id: i32,
+}
+
+impl Seat {
+    pub fn new(row: i32, column: i32, scheme: &Scheme) -> Seat {
+        Seat {
+            row,
+            column,
+            id: scheme.seat_id(row, column),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum ParseSeatError {
+    WrongLength { expected: usize, found: usize },
+    // The character and its position in the pass
+    BadRowChar(usize, char),
+    BadColumnChar(usize, char),
+}
+
+impl fmt::Display for ParseSeatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseSeatError::WrongLength { expected, found } => write!(
+                f,
+                "boarding pass has {} characters, expected {}",
+                found, expected
+            ),
+            ParseSeatError::BadRowChar(at, c) => {
+                write!(f, "{:?} at {} is not a row character", c, at)
+            }
+            ParseSeatError::BadColumnChar(at, c) => {
+                write!(f, "{:?} at {} is not a column character", c, at)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseSeatError {}
+
+impl Scheme {
+    // Each character is one bit of the row or column, most significant first
+    pub fn decode(&self, pass: &str) -> Result<Seat, ParseSeatError> {
+        use ParseSeatError::*;
+        let found = pass.chars().count();
+        if found != self.pass_len() {
+            return Err(WrongLength {
+                expected: self.pass_len(),
+                found,
+            });
+        }
+        // the bad character and its position within the section
+        fn bits(section: &str, (zero, one): (char, char)) -> Result<i32, (usize, char)> {
+            section.chars().enumerate().try_fold(0, |value, (at, c)| {
+                if c == zero {
+                    Ok(value << 1)
+                } else if c == one {
+                    Ok(value << 1 | 1)
+                } else {
+                    Err((at, c))
+                }
+            })
+        }
+        let split = pass
+            .char_indices()
+            .nth(self.row_bits as usize)
+            .map_or(pass.len(), |(i, _)| i);
+        let (row, column) = pass.split_at(split);
+        let row = bits(row, self.row_chars).map_err(|(at, c)| BadRowChar(at, c))?;
+        let column = bits(column, self.column_chars)
+            .map_err(|(at, c)| BadColumnChar(self.row_bits as usize + at, c))?;
+        Ok(Seat::new(row, column, self))
+    }
+}
+
+impl FromStr for Seat {
+    type Err = ParseSeatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Scheme::PUZZLE.decode(s)
+    }
+}
+
+// Panics on a bad pass, see `Scheme::decode` to handle them
+pub fn decode_boarding_pass(pass: &str) -> Seat {
+    decode_with_scheme(pass, &Scheme::PUZZLE)
+}
+
+pub fn decode_with_scheme(pass: &str, scheme: &Scheme) -> Seat {
+    match scheme.decode(pass) {
+        Ok(seat) => seat,
+        Err(e) => panic!("Bad encoding: {}", e),
+    }
+}
+
+pub fn encode_seat(row: i32, column: i32) -> String {
+    Scheme::PUZZLE.encode(row, column)
+}
+
+// The original decoder, kept to check and benchmark the bitwise one against
+pub fn decode_by_halving(pass: &str, scheme: &Scheme) -> Seat {
+    // This algorithm is obtuse and inefficient in Rust
+    // But, hey, it works!
+    // And it was fun to figure out how to make it work
+    fn range_binary_search(
+        range: Box<dyn ExactSizeIterator<Item = u32>>,
+        c: char,
+        (left_char, right_char): (char, char),
+    ) -> Box<dyn ExactSizeIterator<Item = u32>> {
+        let len = range.len();
+        if c == left_char {
+            Box::new(range.take(len / 2))
+        } else if c == right_char {
+            Box::new(range.skip(len / 2))
+        } else {
+            panic!("Bad encoding")
+        }
+    }
+    let row = pass
+        .chars()
+        .take(scheme.row_bits as usize)
+        .fold(
+            Box::new(0..scheme.rows() as u32) as Box<dyn ExactSizeIterator<Item = u32>>,
+            |range, c| range_binary_search(range, c, scheme.row_chars),
+        )
+        .next()
+        .unwrap() as i32;
+    let column = pass
+        .chars()
+        .skip(scheme.row_bits as usize)
+        .take(scheme.column_bits as usize)
+        .fold(
+            Box::new(0..scheme.columns() as u32) as Box<dyn ExactSizeIterator<Item = u32>>,
+            |range, c| range_binary_search(range, c, scheme.column_chars),
+        )
+        .next()
+        .unwrap() as i32;
+    Seat::new(row, column, scheme)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn scheme() -> impl Strategy<Value = Scheme> {
+        (0..16u32, 0..16u32).prop_map(|(rows, columns)| {
+            Scheme::new(rows, columns)
+                .with_row_chars('A', 'Z')
+                .with_column_chars('0', '1')
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn puzzle_round_trip(row in 0..128, column in 0..8) {
+            let pass = encode_seat(row, column);
+            prop_assert_eq!(pass.len(), 10);
+            prop_assert_eq!(decode_boarding_pass(&pass), Seat::new(row, column, &Scheme::PUZZLE));
+        }
+
+        #[test]
+        fn any_scheme_round_trip(scheme in scheme(), row: u32, column: u32) {
+            let row = (row % scheme.rows() as u32) as i32;
+            let column = (column % scheme.columns() as u32) as i32;
+            let pass = scheme.encode(row, column);
+            prop_assert_eq!(pass.len(), scheme.pass_len());
+            prop_assert_eq!(decode_with_scheme(&pass, &scheme), Seat::new(row, column, &scheme));
+        }
+
+        #[test]
+        fn matches_halving(pass in "[FB]{7}[LR]{3}") {
+            prop_assert_eq!(decode_boarding_pass(&pass), decode_by_halving(&pass, &Scheme::PUZZLE));
+            let seat = decode_boarding_pass(&pass);
+            prop_assert_eq!(encode_seat(seat.row, seat.column), pass);
+        }
+
+        #[test]
+        fn wrong_length(pass in "[FBLR]{0,9}|[FBLR]{11,20}") {
+            prop_assert_eq!(
+                pass.parse::<Seat>(),
+                Err(ParseSeatError::WrongLength { expected: 10, found: pass.len() })
+            );
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            "FBFBBFFRLR".parse(),
+            Ok(Seat {
+                row: 44,
+                column: 5,
+                id: 357
+            })
+        );
+        assert_eq!(
+            "FBFBBFFRLRL".parse::<Seat>(),
+            Err(ParseSeatError::WrongLength {
+                expected: 10,
+                found: 11
+            })
+        );
+        assert_eq!(
+            "FBFBBF".parse::<Seat>(),
+            Err(ParseSeatError::WrongLength {
+                expected: 10,
+                found: 6
+            })
+        );
+        assert_eq!(
+            "FBFxBFFRLR".parse::<Seat>(),
+            Err(ParseSeatError::BadRowChar(3, 'x'))
+        );
+        assert_eq!(
+            "FBFBBFLRLR".parse::<Seat>(),
+            Err(ParseSeatError::BadRowChar(6, 'L'))
+        );
+        assert_eq!(
+            "FBFBBFFRFR".parse::<Seat>(),
+            Err(ParseSeatError::BadColumnChar(8, 'F'))
+        );
+        assert_eq!(
+            ParseSeatError::BadColumnChar(8, 'F').to_string(),
+            "'F' at 8 is not a column character"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Bad encoding: boarding pass has 6 characters, expected 10")]
+    fn decode_panics() {
+        decode_boarding_pass("FBFBBF");
+    }
+
+    #[test]
+    fn encode() {
+        assert_eq!(encode_seat(44, 5), "FBFBBFFRLR");
+        assert_eq!(encode_seat(102, 4), "BBFFBBFRLL");
+        let scheme = Scheme::new(4, 2)
+            .with_row_chars('A', 'Z')
+            .with_column_chars('P', 'S');
+        assert_eq!(scheme.encode(11, 2), "ZAZZSP");
+    }
+
+    #[test]
+    #[should_panic(expected = "no seat at row 128 column 0")]
+    fn encode_out_of_range() {
+        encode_seat(128, 0);
+    }
+
+    #[test]
+    fn other_aircraft() {
+        // 16 rows of 4 seats, rows lettered A(ft)/Z(aft), seats P(ort)/S(tarboard)
+        let scheme = Scheme::new(4, 2)
+            .with_row_chars('A', 'Z')
+            .with_column_chars('P', 'S');
+        assert_eq!(scheme.rows(), 16);
+        assert_eq!(scheme.columns(), 4);
+        assert_eq!(scheme.pass_len(), 6);
+        assert_eq!(
+            decode_with_scheme("ZAZZSP", &scheme),
+            Seat {
+                row: 11,
+                column: 2,
+                id: 46
+            }
+        );
+        assert_eq!(decode_with_scheme("AAAAPP", &scheme).id, 0);
+        assert_eq!(decode_with_scheme("ZZZZSS", &scheme).id, 63);
+    }
+
+    #[test]
+    fn largest_scheme() {
+        let scheme = Scheme::new(20, 10);
+        assert_eq!(scheme.rows() * scheme.columns(), 1 << 30);
+        assert_eq!(
+            scheme.seat_id(scheme.rows() - 1, scheme.columns() - 1),
+            (1 << 30) - 1
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "seat ids have to fit in 30 bits")]
+    fn too_many_seats() {
+        Scheme::new(31, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "front and back have to be different characters")]
+    fn same_row_chars() {
+        Scheme::PUZZLE.with_row_chars('F', 'F');
+    }
+
+    #[test]
+    #[should_panic(expected = "left and right have to be different characters")]
+    fn same_column_chars() {
+        Scheme::PUZZLE.with_column_chars('L', 'L');
+    }
+
+    #[test]
+    fn puzzle_is_default() {
+        assert_eq!(Scheme::default(), Scheme::PUZZLE);
+        assert_eq!(Scheme::new(7, 3), Scheme::PUZZLE);
+        assert_eq!(
+            decode_boarding_pass("FBFBBFFRLR"),
+            decode_with_scheme("FBFBBFFRLR", &Scheme::PUZZLE)
+        );
+        assert_eq!(Scheme::PUZZLE.seat_id(44, 5), 357);
+    }
+}
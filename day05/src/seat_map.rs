@@ -0,0 +1,208 @@
+use std::fmt;
+
+use crate::seat::{ParseSeatError, Scheme, Seat};
+
+// Which seats of the cabin have a boarding pass
+#[derive(Debug, Clone)]
+pub struct SeatMap {
+    scheme: Scheme,
+    // indexed by seat id
+    occupied: Vec<bool>,
+}
+
+impl SeatMap {
+    pub fn new(scheme: Scheme) -> SeatMap {
+        SeatMap {
+            scheme,
+            occupied: vec![false; (scheme.rows() * scheme.columns()) as usize],
+        }
+    }
+
+    // One pass per line, in the puzzle scheme, blank lines are skipped
+    pub fn from_passes(passes: &str) -> Result<SeatMap, ParseSeatError> {
+        SeatMap::from_passes_with_scheme(passes, Scheme::PUZZLE)
+    }
+
+    pub fn from_passes_with_scheme(
+        passes: &str,
+        scheme: Scheme,
+    ) -> Result<SeatMap, ParseSeatError> {
+        let mut map = SeatMap::new(scheme);
+        for pass in passes.lines() {
+            let pass = pass.trim();
+            if pass.is_empty() {
+                continue;
+            }
+            map.occupy(scheme.decode(pass)?);
+        }
+        Ok(map)
+    }
+
+    pub fn scheme(&self) -> &Scheme {
+        &self.scheme
+    }
+
+    // Where a seat is in `occupied`, `None` outside the cabin
+    fn index(&self, row: i32, column: i32) -> Option<usize> {
+        if (0..self.scheme.rows()).contains(&row) && (0..self.scheme.columns()).contains(&column) {
+            Some(self.scheme.seat_id(row, column) as usize)
+        } else {
+            None
+        }
+    }
+
+    // `false` for a seat outside the cabin, which is left alone
+    pub fn occupy(&mut self, seat: Seat) -> bool {
+        self.set(seat, true)
+    }
+
+    pub fn release(&mut self, seat: Seat) -> bool {
+        self.set(seat, false)
+    }
+
+    fn set(&mut self, seat: Seat, occupied: bool) -> bool {
+        match self.index(seat.row, seat.column) {
+            Some(index) => {
+                self.occupied[index] = occupied;
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Seats outside the cabin are never occupied
+    pub fn is_occupied(&self, row: i32, column: i32) -> bool {
+        self.index(row, column)
+            .is_some_and(|index| self.occupied[index])
+    }
+
+    pub fn occupied_count(&self) -> usize {
+        self.occupied.iter().filter(|o| **o).count()
+    }
+
+    fn seat(&self, id: usize) -> Seat {
+        let columns = self.scheme.columns() as usize;
+        Seat::new((id / columns) as i32, (id % columns) as i32, &self.scheme)
+    }
+
+    pub fn seats(&self) -> impl Iterator<Item = (Seat, bool)> + '_ {
+        self.occupied
+            .iter()
+            .enumerate()
+            .map(move |(id, occupied)| (self.seat(id), *occupied))
+    }
+
+    pub fn empty_seats(&self) -> Vec<Seat> {
+        self.seats()
+            .filter(|(_, occupied)| !occupied)
+            .map(|(seat, _)| seat)
+            .collect()
+    }
+
+    // Neighbours go by seat id like in the puzzle, so the last seat of one row sits next to
+    // the first seat of the next
+    pub fn empty_between_occupied(&self) -> Vec<Seat> {
+        self.occupied
+            .windows(3)
+            .enumerate()
+            .filter(|(_, w)| w == &[true, false, true])
+            .map(|(id, _)| self.seat(id + 1))
+            .collect()
+    }
+
+    pub fn fully_booked_rows(&self) -> Vec<i32> {
+        self.occupied
+            .chunks(self.scheme.columns() as usize)
+            .enumerate()
+            .filter(|(_, row)| row.iter().all(|o| *o))
+            .map(|(row, _)| row as i32)
+            .collect()
+    }
+}
+
+// One line per row, '#' for an occupied seat and '.' for an empty one,
+// with the aisle down the middle
+impl fmt::Display for SeatMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let columns = self.scheme.columns() as usize;
+        let width = (self.scheme.rows() - 1).to_string().len();
+        for (row, seats) in self.occupied.chunks(columns).enumerate() {
+            write!(f, "{:>width$} ", row, width = width)?;
+            for (column, occupied) in seats.iter().enumerate() {
+                if column == columns / 2 && columns > 1 {
+                    f.write_str(" ")?;
+                }
+                f.write_str(if *occupied { "#" } else { "." })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    // 4 rows of 4 seats, drawn in `diagram`
+    pub(crate) fn small_cabin() -> SeatMap {
+        let scheme = Scheme::new(2, 2);
+        let passes = "FFLL\nFFLR\nFFRR\nFBLL\nFBLR\nFBRL\nFBRR\nBFRR\nBBLL\nBBRL";
+        SeatMap::from_passes_with_scheme(passes, scheme).unwrap()
+    }
+
+    #[test]
+    fn diagram() {
+        let map = small_cabin();
+        assert_eq!(map.occupied_count(), 10);
+        assert_eq!(
+            map.to_string(),
+            "0 ## .#
+1 ## ##
+2 .. .#
+3 #. #.
+"
+        );
+    }
+
+    #[test]
+    fn queries() {
+        let map = small_cabin();
+        let scheme = *map.scheme();
+        assert_eq!(map.fully_booked_rows(), vec![1]);
+        assert_eq!(
+            map.empty_between_occupied(),
+            vec![Seat::new(0, 2, &scheme), Seat::new(3, 1, &scheme)]
+        );
+        assert_eq!(map.empty_seats().len(), 6);
+        assert!(map.is_occupied(3, 2));
+        assert!(!map.is_occupied(3, 3));
+        assert!(!map.is_occupied(4, 0));
+        assert!(!map.is_occupied(0, -1));
+    }
+
+    #[test]
+    fn occupy_and_release() {
+        let mut map = SeatMap::new(Scheme::PUZZLE);
+        let seat: Seat = "FBFBBFFRLR".parse().unwrap();
+        assert!(map.occupy(seat));
+        assert!(map.is_occupied(44, 5));
+        assert!(map.release(seat));
+        assert_eq!(map.occupied_count(), 0);
+        // seats outside the cabin are left alone instead of panicking
+        let outside = Seat::new(128, 0, &Scheme::PUZZLE);
+        assert!(!map.occupy(outside));
+        assert!(!map.release(outside));
+        assert!(!map.occupy(Seat::new(0, -1, &Scheme::PUZZLE)));
+        assert_eq!(map.occupied_count(), 0);
+        let map = SeatMap::from_passes("FBFBBFFRLR\n\n  \nBFFFBBFRRR \n").unwrap();
+        assert_eq!(map.occupied_count(), 2);
+        assert_eq!(
+            SeatMap::from_passes("FBFBBFFRLR\nFBFBBFFRL").unwrap_err(),
+            ParseSeatError::WrongLength {
+                expected: 10,
+                found: 9
+            }
+        );
+    }
+}
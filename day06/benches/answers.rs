@@ -0,0 +1,59 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use day06::group::{parse_groups, Alphabet, Group};
+
+// 10,000 groups of 1 to 5 people with up to 26 answers each, from a fixed xorshift so runs compare
+// Answers aren't repeated within a person, so the groups parse
+fn groups() -> String {
+    let mut state = 0x2545_f491_u32;
+    let mut next = move |n: u32| {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        state % n
+    };
+    (0..10_000)
+        .map(|_| {
+            let people = 1 + next(5);
+            (0..people)
+                .map(|_| {
+                    let mask = next(1 << 26);
+                    ('a'..='z')
+                        .filter(|q| mask & 1 << (*q as u8 - b'a') != 0)
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn answers(c: &mut Criterion) {
+    let input = groups();
+    // the default alphabet keeps bitmasks, one with a question outside 'a' to 'z' keeps
+    // `HashSet`s for the same answers
+    let bitmasks = parse_groups(&input, &Alphabet::default()).unwrap();
+    let sets = Alphabet::new("abcdefghijklmnopqrstuvwxyzA");
+    let sets = parse_groups(&input, &sets).unwrap();
+    let sum = |groups: &[Group], count: fn(&Group) -> usize| {
+        groups.iter().map(|g| count(black_box(g))).sum::<usize>()
+    };
+    let mut group = c.benchmark_group("10k groups");
+    group.bench_function("any yes bitmask", |b| {
+        b.iter(|| sum(&bitmasks, Group::any_yes))
+    });
+    group.bench_function("any yes hash set", |b| {
+        b.iter(|| sum(&sets, Group::any_yes))
+    });
+    group.bench_function("all yes bitmask", |b| {
+        b.iter(|| sum(&bitmasks, Group::all_yes))
+    });
+    group.bench_function("all yes hash set", |b| {
+        b.iter(|| sum(&sets, Group::all_yes))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, answers);
+criterion_main!(benches);
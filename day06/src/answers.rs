@@ -0,0 +1,73 @@
+use crate::sets::SetAlgebra;
+
+// One person's yes answers to questions 'a' to 'z', bit 0 is 'a'
+// `Group` keeps its people this way when its alphabet is within 'a' to 'z'
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy, Default)]
+pub struct Answers(pub u32);
+
+impl Answers {
+    // `false` if the question was already answered
+    pub fn insert(&mut self, question: char) -> bool {
+        assert!(
+            question.is_ascii_lowercase(),
+            "{:?} is not a question from 'a' to 'z'",
+            question
+        );
+        let bit = 1 << (question as u8 - b'a');
+        let new = self.0 & bit == 0;
+        self.0 |= bit;
+        new
+    }
+
+    pub fn contains(&self, question: char) -> bool {
+        question.is_ascii_lowercase() && self.0 & 1 << (question as u8 - b'a') != 0
+    }
+
+    pub fn questions(&self) -> impl Iterator<Item = char> + '_ {
+        ('a'..='z').filter(move |q| self.contains(*q))
+    }
+}
+
+impl SetAlgebra for Answers {
+    fn union_with(&mut self, other: &Self) {
+        self.0.union_with(&other.0);
+    }
+
+    fn intersect_with(&mut self, other: &Self) {
+        self.0.intersect_with(&other.0);
+    }
+
+    fn symmetric_difference_with(&mut self, other: &Self) {
+        self.0.symmetric_difference_with(&other.0);
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert() {
+        let mut answers = Answers::default();
+        assert!(answers.insert('a'));
+        assert!(answers.insert('b'));
+        assert!(answers.insert('z'));
+        assert!(!answers.insert('a'));
+        assert_eq!(answers, Answers(1 | 2 | 1 << 25));
+        assert_eq!(answers.questions().collect::<String>(), "abz");
+        assert_eq!(answers.len(), 3);
+        assert!(answers.contains('z'));
+        assert!(!answers.contains('c'));
+        assert!(!answers.contains('A'));
+    }
+
+    #[test]
+    #[should_panic(expected = "'B' is not a question from 'a' to 'z'")]
+    fn insert_outside_alphabet() {
+        Answers::default().insert('B');
+    }
+}
@@ -0,0 +1,335 @@
+use std::{collections::HashSet, fmt};
+
+use records::records;
+
+use crate::{
+    answers::Answers,
+    sets::{intersection, union, SetAlgebra},
+};
+
+// The characters that are questions, 'a' to 'z' by default
+// Whitespace separates answers so it can never be a question
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Alphabet {
+    questions: Vec<char>,
+}
+
+impl Alphabet {
+    pub fn new(questions: &str) -> Alphabet {
+        let mut seen = HashSet::new();
+        Alphabet {
+            questions: questions
+                .chars()
+                .filter(|c| !c.is_whitespace() && seen.insert(*c))
+                .collect(),
+        }
+    }
+
+    pub fn contains(&self, question: char) -> bool {
+        self.questions.contains(&question)
+    }
+
+    pub fn questions(&self) -> &[char] {
+        &self.questions
+    }
+
+    // Whether answers fit in `Answers` bitmasks
+    fn is_lowercase(&self) -> bool {
+        self.questions.iter().all(char::is_ascii_lowercase)
+    }
+}
+
+impl Default for Alphabet {
+    fn default() -> Alphabet {
+        Alphabet::new("abcdefghijklmnopqrstuvwxyz")
+    }
+}
+
+// Lines count from 1, within whatever text was parsed
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum AnswerError {
+    InvalidQuestion { line: usize, question: char },
+    // One person answering the same question twice
+    DuplicateAnswer { line: usize, question: char },
+}
+
+impl AnswerError {
+    pub fn line(&self) -> usize {
+        match self {
+            AnswerError::InvalidQuestion { line, .. }
+            | AnswerError::DuplicateAnswer { line, .. } => *line,
+        }
+    }
+
+    // The same error with its line counted from `first_line` instead of 1
+    fn starting_at(self, first_line: usize) -> AnswerError {
+        match self {
+            AnswerError::InvalidQuestion { line, question } => AnswerError::InvalidQuestion {
+                line: line + first_line - 1,
+                question,
+            },
+            AnswerError::DuplicateAnswer { line, question } => AnswerError::DuplicateAnswer {
+                line: line + first_line - 1,
+                question,
+            },
+        }
+    }
+}
+
+impl fmt::Display for AnswerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnswerError::InvalidQuestion { line, question } => {
+                write!(f, "line {}: {:?} is not a question", line, question)
+            }
+            AnswerError::DuplicateAnswer { line, question } => {
+                write!(
+                    f,
+                    "line {}: {:?} is answered more than once",
+                    line, question
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for AnswerError {}
+
+// The people in a group with the line each is on, counting from 1
+// Blank lines are nobody
+pub fn people(group: &str) -> impl Iterator<Item = (usize, &str)> {
+    group
+        .lines()
+        .enumerate()
+        .map(|(i, person)| (i + 1, person))
+        .filter(|(_, person)| !person.trim().is_empty())
+}
+
+// One person's yes answers, whitespace separates answers so it's never one
+pub fn yes_answers(person: &str) -> impl Iterator<Item = char> + '_ {
+    person.chars().filter(|c| !c.is_whitespace())
+}
+
+// One group's answers, one set of yes answers per person
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct Group {
+    people: People,
+}
+
+// Bitmasks when the alphabet is within 'a' to 'z', `HashSet`s for anything else
+#[derive(Debug, Eq, PartialEq, Clone)]
+enum People {
+    Bitmasks(Vec<Answers>),
+    Sets(Vec<HashSet<char>>),
+}
+
+impl Default for People {
+    fn default() -> People {
+        People::Bitmasks(Vec::new())
+    }
+}
+
+// Each person's answers checked against `alphabet`, `insert` is `false` for a repeated one
+fn parse_people<S: Default>(
+    group: &str,
+    alphabet: &Alphabet,
+    insert: impl Fn(&mut S, char) -> bool,
+) -> Result<Vec<S>, AnswerError> {
+    let mut everyone = Vec::new();
+    for (line, person) in people(group) {
+        let mut answers = S::default();
+        for question in yes_answers(person) {
+            if !alphabet.contains(question) {
+                return Err(AnswerError::InvalidQuestion { line, question });
+            }
+            if !insert(&mut answers, question) {
+                return Err(AnswerError::DuplicateAnswer { line, question });
+            }
+        }
+        everyone.push(answers);
+    }
+    Ok(everyone)
+}
+
+impl Group {
+    // One person per line, see `people`
+    pub fn parse(group: &str, alphabet: &Alphabet) -> Result<Group, AnswerError> {
+        let people = if alphabet.is_lowercase() {
+            People::Bitmasks(parse_people(group, alphabet, Answers::insert)?)
+        } else {
+            People::Sets(parse_people(group, alphabet, HashSet::insert)?)
+        };
+        Ok(Group { people })
+    }
+
+    // How many people are in the group
+    pub fn size(&self) -> usize {
+        match &self.people {
+            People::Bitmasks(people) => people.len(),
+            People::Sets(people) => people.len(),
+        }
+    }
+
+    // Every yes answer, once for each person who gave it
+    pub fn answers(&self) -> Vec<char> {
+        match &self.people {
+            People::Bitmasks(people) => people.iter().flat_map(|p| p.questions()).collect(),
+            People::Sets(people) => people.iter().flatten().copied().collect(),
+        }
+    }
+
+    // An empty group has nobody to answer yes, so both counts are 0
+    pub fn any_yes(&self) -> usize {
+        match &self.people {
+            People::Bitmasks(people) => union(people.iter().copied()).len(),
+            People::Sets(people) => union(people.iter().cloned()).len(),
+        }
+    }
+
+    pub fn all_yes(&self) -> usize {
+        match &self.people {
+            People::Bitmasks(people) => {
+                intersection(people.iter().copied()).map_or(0, |set| set.len())
+            }
+            People::Sets(people) => intersection(people.iter().cloned()).map_or(0, |set| set.len()),
+        }
+    }
+}
+
+// Every group in a batch, the first error has its line in the whole input
+pub fn parse_groups(input: &str, alphabet: &Alphabet) -> Result<Vec<Group>, AnswerError> {
+    records(input)
+        .map(|r| Group::parse(&r.text, alphabet).map_err(|e| e.starting_at(r.line)))
+        .collect()
+}
+
+// The same, with the line each group starts on
+pub fn parse_groups_with_lines(
+    input: &str,
+    alphabet: &Alphabet,
+) -> Result<Vec<(usize, Group)>, AnswerError> {
+    records(input)
+        .map(|r| {
+            Group::parse(&r.text, alphabet)
+                .map(|group| (r.line, group))
+                .map_err(|e| e.starting_at(r.line))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alphabet() {
+        let alphabet = Alphabet::new("xyz x\t1");
+        assert_eq!(alphabet.questions(), &['x', 'y', 'z', '1']);
+        assert!(alphabet.contains('1'));
+        assert!(!alphabet.contains(' '));
+        assert_eq!(Alphabet::default().questions().len(), 26);
+    }
+
+    #[test]
+    fn people_and_answers() {
+        let group = "ab \r\n\n \t\nc\ta\r";
+        assert_eq!(
+            people(group).collect::<Vec<_>>(),
+            vec![(1, "ab "), (4, "c\ta\r")]
+        );
+        assert_eq!(yes_answers("c\ta\r").collect::<String>(), "ca");
+    }
+
+    #[test]
+    fn counts() {
+        let group = Group::parse("ab\nac\n", &Alphabet::default()).unwrap();
+        assert_eq!(group.size(), 2);
+        assert_eq!(group.answers(), vec!['a', 'b', 'a', 'c']);
+        assert_eq!(group.any_yes(), 3);
+        assert_eq!(group.all_yes(), 1);
+        let group = Group::parse("12\n21 3", &Alphabet::new("0123")).unwrap();
+        assert_eq!(group.size(), 2);
+        assert_eq!(group.any_yes(), 3);
+        assert_eq!(group.all_yes(), 2);
+        let group = Group::parse("aé\nAé", &Alphabet::new("aAé")).unwrap();
+        assert_eq!(group.any_yes(), 3);
+        assert_eq!(group.all_yes(), 1);
+    }
+
+    #[test]
+    fn bitmasks_and_hash_sets_agree() {
+        let bitmasks = Alphabet::default();
+        // not all lower case, so its groups keep `HashSet`s
+        let sets = Alphabet::new("abcdefghijklmnopqrstuvwxyzA");
+        for group in &[
+            "abc",
+            "a\nb\nc",
+            "ab\nac",
+            "a\na\na\na",
+            "b",
+            "xyz\nzyx\nyz",
+            "ab\r\nac\r\n \r\n",
+            "a b\r\nba\r",
+            "",
+        ] {
+            let bitmasks = Group::parse(group, &bitmasks).unwrap();
+            let sets = Group::parse(group, &sets).unwrap();
+            assert!(matches!(bitmasks.people, People::Bitmasks(_)));
+            assert!(matches!(sets.people, People::Sets(_)));
+            assert_eq!(bitmasks.size(), sets.size());
+            assert_eq!(bitmasks.any_yes(), sets.any_yes());
+            assert_eq!(bitmasks.all_yes(), sets.all_yes());
+            let mut answers = sets.answers();
+            answers.sort_unstable();
+            let mut expected = bitmasks.answers();
+            expected.sort_unstable();
+            assert_eq!(answers, expected);
+        }
+    }
+
+    #[test]
+    fn empty_groups() {
+        for group in &["", "\n", "  \n\t"] {
+            let group = Group::parse(group, &Alphabet::default()).unwrap();
+            assert_eq!(group.size(), 0);
+            assert_eq!(group.any_yes(), 0);
+            assert_eq!(group.all_yes(), 0);
+        }
+        assert_eq!(Group::default().all_yes(), 0);
+    }
+
+    #[test]
+    fn errors() {
+        let alphabet = Alphabet::default();
+        assert_eq!(
+            Group::parse("ab\naB", &alphabet),
+            Err(AnswerError::InvalidQuestion {
+                line: 2,
+                question: 'B'
+            })
+        );
+        assert_eq!(
+            Group::parse("aba", &alphabet),
+            Err(AnswerError::DuplicateAnswer {
+                line: 1,
+                question: 'a'
+            })
+        );
+        let err = parse_groups("abc\n\na\nb\n\nab\nacc", &alphabet).unwrap_err();
+        assert_eq!(
+            err,
+            AnswerError::DuplicateAnswer {
+                line: 7,
+                question: 'c'
+            }
+        );
+        assert_eq!(err.line(), 7);
+        assert_eq!(err.to_string(), "line 7: 'c' is answered more than once");
+        assert_eq!(parse_groups("abc\n\na\nb", &alphabet).unwrap().len(), 2);
+        let groups = parse_groups_with_lines("abc\n\n\na\nb", &alphabet).unwrap();
+        assert_eq!(
+            groups.iter().map(|(line, _)| *line).collect::<Vec<_>>(),
+            vec![1, 4]
+        );
+    }
+}
@@ -0,0 +1,4 @@
+pub mod answers;
+pub mod group;
+pub mod sets;
+pub mod stats;
@@ -1,65 +1,64 @@
-use std::{collections::HashSet, fs::read_to_string};
+use std::{env, fs::read_to_string};
+
+use day06::{
+    group::{parse_groups, Alphabet, Group},
+    stats::Report,
+};
 
 fn main() {
     let input = read_to_string("input.txt").expect("error reading input file");
+    let alphabet = Alphabet::default();
+    let groups = match parse_groups(&input, &alphabet) {
+        Ok(groups) => groups,
+        Err(e) => {
+            println!("Bad customs answers, {}", e);
+            return;
+        }
+    };
     println!("Part 1:");
-    println!("Total yes answers: {}", sum_groups_yes(&input));
+    println!("Total yes answers: {}", sum_groups_yes(&groups));
     println!("Part 2:");
-    println!("Total yes answers: {}", sum_groups_all_yes(&input));
-}
+    println!("Total yes answers: {}", sum_groups_all_yes(&groups));
 
-fn sum_groups_yes(answers: &str) -> usize {
-    answers
-        .split("\n\n")
-        .map(num_yes_to_questions_in_group)
-        .sum()
+    // `--stats` breaks the answers down by question and group size
+    if env::args().any(|a| a == "--stats") {
+        // the same check as above, so it can't fail here
+        print!("{}", Report::new(&input, &alphabet).unwrap());
+    }
 }
 
-fn num_yes_to_questions_in_group(answers: &str) -> usize {
-    let mut set = HashSet::new();
-    for question in answers.chars().filter(|c| !c.is_whitespace()) {
-        set.insert(question);
-    }
-    set.len()
+fn sum_groups_yes(groups: &[Group]) -> usize {
+    groups.iter().map(num_yes_to_questions_in_group).sum()
 }
 
-fn sum_groups_all_yes(answers: &str) -> usize {
-    answers
-        .split("\n\n")
-        .map(num_all_yes_to_questions_in_group)
-        .sum()
+fn num_yes_to_questions_in_group(group: &Group) -> usize {
+    group.any_yes()
 }
 
-fn num_all_yes_to_questions_in_group(answers: &str) -> usize {
-    let sets = answers
-        .lines()
-        .map(|s| s.chars().collect::<HashSet<_>>())
-        .collect::<Vec<_>>();
-    intersection_many_sets(&sets).unwrap().count()
+fn sum_groups_all_yes(groups: &[Group]) -> usize {
+    groups.iter().map(num_all_yes_to_questions_in_group).sum()
 }
 
-// `HashSet` has fn `intersection` but only gives the result for 2 sets
-// This fn takes a slice of `HashSet`s and returns `Some` iterator of intersecting elements
-// returns `None` if input `sets` contains 0 `HashSet`s
-fn intersection_many_sets<'iter, T>(
-    sets: &'iter [HashSet<T>],
-) -> Option<impl Iterator<Item = &'iter T>>
-where
-    T: std::cmp::Eq,
-    T: std::hash::Hash,
-    T: 'iter,
-{
-    sets.iter().next().map(move |first_set| {
-        first_set
-            .iter()
-            .filter(move |item| sets.iter().all(|s| s.contains(*item)))
-    })
+fn num_all_yes_to_questions_in_group(group: &Group) -> usize {
+    group.all_yes()
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
+    use day06::sets::intersection;
+
     use super::*;
 
+    fn groups(input: &str) -> Vec<Group> {
+        parse_groups(input, &Alphabet::default()).unwrap()
+    }
+
+    fn group(input: &str) -> Group {
+        Group::parse(input, &Alphabet::default()).unwrap()
+    }
+
     #[test]
     fn example() {
         let input = "abc
@@ -77,26 +76,26 @@ a
 a
 
 b";
-        assert_eq!(sum_groups_yes(input), 11);
+        assert_eq!(sum_groups_yes(&groups(input)), 11);
     }
     #[test]
     fn single_group() {
         let input = "abc";
-        assert_eq!(num_yes_to_questions_in_group(input), 3);
+        assert_eq!(num_yes_to_questions_in_group(&group(input)), 3);
         let input = "a
 b
 c";
-        assert_eq!(num_yes_to_questions_in_group(input), 3);
+        assert_eq!(num_yes_to_questions_in_group(&group(input)), 3);
         let input = "ab
 ac";
-        assert_eq!(num_yes_to_questions_in_group(input), 3);
+        assert_eq!(num_yes_to_questions_in_group(&group(input)), 3);
         let input = "a
 a
 a
 a";
-        assert_eq!(num_yes_to_questions_in_group(input), 1);
+        assert_eq!(num_yes_to_questions_in_group(&group(input)), 1);
         let input = "b";
-        assert_eq!(num_yes_to_questions_in_group(input), 1);
+        assert_eq!(num_yes_to_questions_in_group(&group(input)), 1);
     }
 
     #[test]
@@ -116,26 +115,38 @@ a
 a
 
 b";
-        assert_eq!(sum_groups_all_yes(input), 6);
+        assert_eq!(sum_groups_all_yes(&groups(input)), 6);
+    }
+    #[test]
+    fn crlf_and_messy_blank_lines() {
+        let input = "abc\r\n\r\n \r\n\r\na\r\nb\r\nc\r\n\r\nab\r\nac\r\n\r\n";
+        assert_eq!(sum_groups_yes(&groups(input)), 9);
+        assert_eq!(sum_groups_all_yes(&groups(input)), 4);
+    }
+    #[test]
+    fn whitespace_and_bad_answers() {
+        assert_eq!(num_yes_to_questions_in_group(&group("ab \nab ")), 2);
+        assert_eq!(num_all_yes_to_questions_in_group(&group("ab \nab ")), 2);
+        assert!(parse_groups("ab\n\nab\naB", &Alphabet::default()).is_err());
     }
     #[test]
     fn part2_single_group() {
         let input = "abc";
-        assert_eq!(num_all_yes_to_questions_in_group(input), 3);
+        assert_eq!(num_all_yes_to_questions_in_group(&group(input)), 3);
         let input = "a
 b
 c";
-        assert_eq!(num_all_yes_to_questions_in_group(input), 0);
+        assert_eq!(num_all_yes_to_questions_in_group(&group(input)), 0);
         let input = "ab
 ac";
-        assert_eq!(num_all_yes_to_questions_in_group(input), 1);
+        assert_eq!(num_all_yes_to_questions_in_group(&group(input)), 1);
         let input = "a
 a
 a
 a";
-        assert_eq!(num_all_yes_to_questions_in_group(input), 1);
+        assert_eq!(num_all_yes_to_questions_in_group(&group(input)), 1);
         let input = "b";
-        assert_eq!(num_all_yes_to_questions_in_group(input), 1);
+        assert_eq!(num_all_yes_to_questions_in_group(&group(input)), 1);
     }
 
     #[test]
@@ -145,10 +156,10 @@ a";
             [1, 4, 5].iter().cloned().collect(),
             [1, 6, 7].iter().cloned().collect(),
         ];
-        let intersection = intersection_many_sets(sets);
+        let intersection = intersection(sets.iter().cloned());
         assert!(intersection.is_some());
-        let mut intersection = intersection.unwrap();
-        assert_eq!(intersection.next(), Some(&1));
+        let mut intersection = intersection.unwrap().into_iter();
+        assert_eq!(intersection.next(), Some(1));
         assert_eq!(intersection.next(), None);
     }
 }
@@ -0,0 +1,203 @@
+use std::{
+    collections::{BTreeSet, HashSet},
+    hash::{BuildHasher, Hash},
+};
+
+// What the n-ary operations below need from a set, in place so bitsets stay cheap
+// `Default` is the empty set
+pub trait SetAlgebra: Default {
+    fn union_with(&mut self, other: &Self);
+    fn intersect_with(&mut self, other: &Self);
+    fn symmetric_difference_with(&mut self, other: &Self);
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T, S> SetAlgebra for HashSet<T, S>
+where
+    T: Eq + Hash + Clone,
+    S: BuildHasher + Default,
+{
+    fn union_with(&mut self, other: &Self) {
+        self.extend(other.iter().cloned());
+    }
+
+    fn intersect_with(&mut self, other: &Self) {
+        self.retain(|item| other.contains(item));
+    }
+
+    fn symmetric_difference_with(&mut self, other: &Self) {
+        for item in other {
+            if !self.remove(item) {
+                self.insert(item.clone());
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        HashSet::len(self)
+    }
+}
+
+impl<T: Ord + Clone> SetAlgebra for BTreeSet<T> {
+    fn union_with(&mut self, other: &Self) {
+        self.extend(other.iter().cloned());
+    }
+
+    fn intersect_with(&mut self, other: &Self) {
+        self.retain(|item| other.contains(item));
+    }
+
+    fn symmetric_difference_with(&mut self, other: &Self) {
+        for item in other {
+            if !self.remove(item) {
+                self.insert(item.clone());
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        BTreeSet::len(self)
+    }
+}
+
+// Plain integers as bitsets, bit n set means n is in the set
+macro_rules! bitset {
+    ($($int:ty),*) => {
+        $(
+            impl SetAlgebra for $int {
+                fn union_with(&mut self, other: &Self) {
+                    *self |= other;
+                }
+
+                fn intersect_with(&mut self, other: &Self) {
+                    *self &= other;
+                }
+
+                fn symmetric_difference_with(&mut self, other: &Self) {
+                    *self ^= other;
+                }
+
+                fn len(&self) -> usize {
+                    self.count_ones() as usize
+                }
+            }
+        )*
+    };
+}
+
+bitset!(u8, u16, u32, u64, u128);
+
+// Everything in any of the sets, empty if there are none
+pub fn union<S: SetAlgebra, I: IntoIterator<Item = S>>(sets: I) -> S {
+    sets.into_iter().fold(S::default(), |mut acc, set| {
+        acc.union_with(&set);
+        acc
+    })
+}
+
+// Everything in all of the sets
+// `None` if there are no sets, there's no empty intersection to give without knowing the universe
+pub fn intersection<S: SetAlgebra, I: IntoIterator<Item = S>>(sets: I) -> Option<S> {
+    let mut sets = sets.into_iter();
+    let first = sets.next()?;
+    Some(sets.fold(first, |mut acc, set| {
+        acc.intersect_with(&set);
+        acc
+    }))
+}
+
+// Everything in an odd number of the sets, which is what chaining the two set version gives
+pub fn symmetric_difference<S: SetAlgebra, I: IntoIterator<Item = S>>(sets: I) -> S {
+    sets.into_iter().fold(S::default(), |mut acc, set| {
+        acc.symmetric_difference_with(&set);
+        acc
+    })
+}
+
+// Everything in at least `k` of the sets
+// `at_least[j]` holds what's been seen in more than `j` sets so far, each new set moves
+// what it shares with one count up to the next
+// `k` of 0 gives the union, anything past the number of sets gives the empty set
+pub fn at_least<S, I>(k: usize, sets: I) -> S
+where
+    S: SetAlgebra + Clone,
+    I: IntoIterator<Item = S>,
+{
+    let k = k.max(1);
+    let mut at_least = vec![S::default(); k];
+    for set in sets {
+        for j in (1..k).rev() {
+            let mut promoted = at_least[j - 1].clone();
+            promoted.intersect_with(&set);
+            at_least[j].union_with(&promoted);
+        }
+        at_least[0].union_with(&set);
+    }
+    at_least.pop().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash_sets() -> Vec<HashSet<char>> {
+        vec![
+            "abc".chars().collect(),
+            "bcd".chars().collect(),
+            "cde".chars().collect(),
+        ]
+    }
+
+    fn sorted(set: HashSet<char>) -> String {
+        let mut chars: Vec<_> = set.into_iter().collect();
+        chars.sort_unstable();
+        chars.into_iter().collect()
+    }
+
+    #[test]
+    fn hash_set() {
+        assert_eq!(sorted(union(hash_sets())), "abcde");
+        assert_eq!(sorted(intersection(hash_sets()).unwrap()), "c");
+        assert_eq!(sorted(symmetric_difference(hash_sets())), "ace");
+        assert_eq!(sorted(at_least(2, hash_sets())), "bcd");
+        assert_eq!(sorted(at_least(3, hash_sets())), "c");
+        assert_eq!(sorted(at_least(4, hash_sets())), "");
+        assert_eq!(sorted(at_least(0, hash_sets())), "abcde");
+    }
+
+    #[test]
+    fn btree_set() {
+        let sets: Vec<BTreeSet<i32>> = vec![
+            [1, 2, 3].iter().cloned().collect(),
+            [2, 3, 4].iter().cloned().collect(),
+            [3, 4, 5].iter().cloned().collect(),
+        ];
+        let items = |set: BTreeSet<i32>| set.into_iter().collect::<Vec<_>>();
+        assert_eq!(items(union(sets.clone())), vec![1, 2, 3, 4, 5]);
+        assert_eq!(items(intersection(sets.clone()).unwrap()), vec![3]);
+        assert_eq!(items(symmetric_difference(sets.clone())), vec![1, 3, 5]);
+        assert_eq!(items(at_least(2, sets)), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn bitset() {
+        let sets = [0b00111u32, 0b01110, 0b11100];
+        assert_eq!(union(sets.iter().cloned()), 0b11111);
+        assert_eq!(intersection(sets.iter().cloned()), Some(0b00100));
+        assert_eq!(symmetric_difference(sets.iter().cloned()), 0b10101);
+        assert_eq!(at_least(2, sets.iter().cloned()), 0b01110);
+        assert_eq!(at_least(2, sets.iter().cloned()).len(), 3);
+    }
+
+    #[test]
+    fn no_sets() {
+        assert_eq!(union(Vec::<u64>::new()), 0);
+        assert_eq!(intersection(Vec::<u64>::new()), None);
+        assert!(symmetric_difference(Vec::<BTreeSet<u8>>::new()).is_empty());
+        assert_eq!(at_least(1, Vec::<u16>::new()), 0);
+    }
+}
@@ -0,0 +1,225 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::group::{parse_groups_with_lines, Alphabet, AnswerError, Group};
+
+// How many people in one group answered yes to each question
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct GroupStats {
+    // where the group starts in the input, counting from 1
+    pub line: usize,
+    pub size: usize,
+    pub yes_counts: BTreeMap<char, usize>,
+}
+
+impl GroupStats {
+    pub fn new(line: usize, group: &Group) -> GroupStats {
+        let mut yes_counts = BTreeMap::new();
+        for question in group.answers() {
+            *yes_counts.entry(question).or_insert(0) += 1;
+        }
+        GroupStats {
+            line,
+            size: group.size(),
+            yes_counts,
+        }
+    }
+
+    pub fn any_yes(&self) -> usize {
+        self.yes_counts.len()
+    }
+
+    pub fn all_yes(&self) -> usize {
+        self.at_least_percent(100).len()
+    }
+
+    // Questions at least `percent` of the group answered yes to
+    pub fn at_least_percent(&self, percent: usize) -> Vec<char> {
+        self.yes_counts
+            .iter()
+            .filter(|(_, count)| *count * 100 >= percent * self.size)
+            .map(|(question, _)| *question)
+            .collect()
+    }
+}
+
+// Statistics over every group in the input
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Report {
+    pub groups: Vec<GroupStats>,
+    // people answering yes to each question, across all groups
+    pub yes_counts: BTreeMap<char, usize>,
+    // group size to the number of groups that size
+    pub group_sizes: BTreeMap<usize, usize>,
+}
+
+impl Report {
+    // Fails on the first answer that isn't in `alphabet`, like `parse_groups`
+    pub fn new(input: &str, alphabet: &Alphabet) -> Result<Report, AnswerError> {
+        let groups: Vec<_> = parse_groups_with_lines(input, alphabet)?
+            .iter()
+            .map(|(line, group)| GroupStats::new(*line, group))
+            .collect();
+        let mut yes_counts = BTreeMap::new();
+        let mut group_sizes = BTreeMap::new();
+        for group in &groups {
+            for (question, count) in &group.yes_counts {
+                *yes_counts.entry(*question).or_insert(0) += count;
+            }
+            *group_sizes.entry(group.size).or_insert(0) += 1;
+        }
+        Ok(Report {
+            groups,
+            yes_counts,
+            group_sizes,
+        })
+    }
+
+    pub fn people(&self) -> usize {
+        self.groups.iter().map(|g| g.size).sum()
+    }
+
+    fn with_count(&self, count: Option<usize>) -> Vec<(char, usize)> {
+        self.yes_counts
+            .iter()
+            .filter(|(_, c)| Some(**c) == count)
+            .map(|(question, count)| (*question, *count))
+            .collect()
+    }
+
+    // Every question sharing the highest count, empty when nobody answered anything
+    pub fn most_common(&self) -> Vec<(char, usize)> {
+        self.with_count(self.yes_counts.values().max().copied())
+    }
+
+    // Only questions somebody answered, a question nobody answered isn't in the input at all
+    pub fn least_common(&self) -> Vec<(char, usize)> {
+        self.with_count(self.yes_counts.values().min().copied())
+    }
+
+    // For each group, the questions at least `percent` of it answered yes to
+    pub fn at_least_percent(&self, percent: usize) -> Vec<(usize, Vec<char>)> {
+        self.groups
+            .iter()
+            .map(|g| (g.line, g.at_least_percent(percent)))
+            .collect()
+    }
+}
+
+fn write_counts(f: &mut fmt::Formatter<'_>, counts: &[(char, usize)]) -> fmt::Result {
+    let counts: Vec<_> = counts
+        .iter()
+        .map(|(question, count)| format!("{} ({})", question, count))
+        .collect();
+    writeln!(f, "{}", counts.join(", "))
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} groups, {} people", self.groups.len(), self.people())?;
+        let sizes: Vec<_> = self
+            .group_sizes
+            .iter()
+            .map(|(size, groups)| format!("{}: {}", size, groups))
+            .collect();
+        writeln!(f, "Group sizes: {}", sizes.join(", "))?;
+        f.write_str("Most common: ")?;
+        write_counts(f, &self.most_common())?;
+        f.write_str("Least common: ")?;
+        write_counts(f, &self.least_common())?;
+        f.write_str("Yes answers: ")?;
+        let counts: Vec<_> = self.yes_counts.iter().map(|(q, c)| (*q, *c)).collect();
+        write_counts(f, &counts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "abc
+
+a
+b
+c
+
+ab
+ac
+
+a
+a
+a
+a
+
+b";
+
+    #[test]
+    fn groups() {
+        let group = Group::parse("ab\nac\n\nb", &Alphabet::default()).unwrap();
+        let group = GroupStats::new(7, &group);
+        assert_eq!(group.size, 3);
+        assert_eq!(
+            group.yes_counts,
+            vec![('a', 2), ('b', 2), ('c', 1)].into_iter().collect()
+        );
+        assert_eq!(group.any_yes(), 3);
+        assert_eq!(group.all_yes(), 0);
+        assert_eq!(group.at_least_percent(50), vec!['a', 'b']);
+        assert_eq!(group.at_least_percent(0), vec!['a', 'b', 'c']);
+    }
+
+    #[test]
+    fn report() {
+        let report = Report::new(EXAMPLE, &Alphabet::default()).unwrap();
+        assert_eq!(report.people(), 11);
+        assert_eq!(
+            report.yes_counts,
+            vec![('a', 8), ('b', 4), ('c', 3)].into_iter().collect()
+        );
+        assert_eq!(
+            report.group_sizes,
+            vec![(1, 2), (2, 1), (3, 1), (4, 1)].into_iter().collect()
+        );
+        assert_eq!(report.most_common(), vec![('a', 8)]);
+        assert_eq!(report.least_common(), vec![('c', 3)]);
+        assert_eq!(
+            report.at_least_percent(50),
+            vec![
+                (1, vec!['a', 'b', 'c']),
+                (3, vec![]),
+                (7, vec!['a', 'b', 'c']),
+                (10, vec!['a']),
+                (15, vec!['b'])
+            ]
+        );
+        let total: usize = report.groups.iter().map(|g| g.all_yes()).sum();
+        assert_eq!(total, 6);
+        assert_eq!(
+            report.to_string(),
+            "5 groups, 11 people
+Group sizes: 1: 2, 2: 1, 3: 1, 4: 1
+Most common: a (8)
+Least common: c (3)
+Yes answers: a (8), b (4), c (3)
+"
+        );
+    }
+
+    #[test]
+    fn empty_input() {
+        let report = Report::new("", &Alphabet::default()).unwrap();
+        assert!(report.groups.is_empty());
+        assert!(report.most_common().is_empty());
+        assert!(report.least_common().is_empty());
+    }
+
+    #[test]
+    fn bad_answers() {
+        assert_eq!(
+            Report::new("ab\n\nacc", &Alphabet::default()).unwrap_err(),
+            AnswerError::DuplicateAnswer {
+                line: 3,
+                question: 'c'
+            }
+        );
+    }
+}
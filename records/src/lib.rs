@@ -0,0 +1,139 @@
+// Splits puzzle input into records separated by blank lines, like the day 4 passports and
+// the day 6 customs groups
+// Handles CRLF line endings, separators that are only whitespace and runs of several blank lines
+use std::{
+    convert::Infallible,
+    io::{self, BufRead},
+};
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Record {
+    // 1-based line number of the first line of the record
+    pub line: usize,
+    // The record's lines joined with '\n', without any '\r'
+    pub text: String,
+}
+
+fn next_record<I, E>(lines: &mut I, line_no: &mut usize) -> Option<Result<Record, E>>
+where
+    I: Iterator<Item = Result<String, E>>,
+{
+    let mut record: Option<Record> = None;
+    for line in lines {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => return Some(Err(e)),
+        };
+        *line_no += 1;
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() {
+            if record.is_some() {
+                break;
+            }
+            continue;
+        }
+        match &mut record {
+            Some(record) => {
+                record.text.push('\n');
+                record.text.push_str(line);
+            }
+            None => {
+                record = Some(Record {
+                    line: *line_no,
+                    text: line.to_string(),
+                })
+            }
+        }
+    }
+    record.map(Ok)
+}
+
+pub struct Records<'a> {
+    lines: std::str::Lines<'a>,
+    line_no: usize,
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = Record;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut lines = self
+            .lines
+            .by_ref()
+            .map(|l| Ok::<_, Infallible>(l.to_string()));
+        next_record(&mut lines, &mut self.line_no).map(|r| match r {
+            Ok(record) => record,
+            Err(e) => match e {},
+        })
+    }
+}
+
+pub fn records(input: &str) -> Records<'_> {
+    Records {
+        lines: input.lines(),
+        line_no: 0,
+    }
+}
+
+// Same as `records` but reads as it goes, for inputs too big to load at once
+pub struct ReadRecords<R> {
+    lines: io::Lines<R>,
+    line_no: usize,
+}
+
+impl<R: BufRead> Iterator for ReadRecords<R> {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_record(&mut self.lines, &mut self.line_no)
+    }
+}
+
+pub fn read_records<R: BufRead>(reader: R) -> ReadRecords<R> {
+    ReadRecords {
+        lines: reader.lines(),
+        line_no: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(line: usize, text: &str) -> Record {
+        Record {
+            line,
+            text: text.into(),
+        }
+    }
+
+    #[test]
+    fn split_on_blank_lines() {
+        let input = "a\nb\n\nc\n";
+        assert_eq!(
+            records(input).collect::<Vec<_>>(),
+            vec![record(1, "a\nb"), record(4, "c")]
+        );
+    }
+
+    #[test]
+    fn messy_separators() {
+        let input = "\r\n\r\na b\r\nc\r\n  \t\r\n\r\n\r\nd\r\n   \n";
+        assert_eq!(
+            records(input).collect::<Vec<_>>(),
+            vec![record(3, "a b\nc"), record(8, "d")]
+        );
+        assert_eq!(records("").count(), 0);
+        assert_eq!(records("\n \n").count(), 0);
+    }
+
+    #[test]
+    fn streaming() {
+        let input = "a\r\n\r\nb\nc";
+        let read: Vec<_> = read_records(input.as_bytes())
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(read, vec![record(1, "a"), record(3, "b\nc")]);
+        assert_eq!(read, records(input).collect::<Vec<_>>());
+    }
+}